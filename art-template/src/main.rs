@@ -1,12 +1,8 @@
 // native app entry_point
 mod sketch;
 
-use async_std::task::block_on;
-use sketch::run_app;
-
+use sketch::Model;
 
 fn main() {
-    block_on(async {
-        run_app().await;
-    });
+    sketch_runtime::run::<Model>();
 }
@@ -0,0 +1,3 @@
+mod sketch;
+
+pub use sketch::Model;
@@ -0,0 +1,4 @@
+mod model;
+mod sketch;
+
+pub use model::Model;
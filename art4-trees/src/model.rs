@@ -2,9 +2,10 @@ use std::fmt::Debug;
 
 use nannou::{
     prelude::{vec2, Hsl, Point2, ToPrimitive, Vec2Rotate, PI},
-    rand::{self, random, random_range, seq::SliceRandom},
+    rand::{self, seq::SliceRandom},
     Draw,
 };
+use sketch_runtime::Random;
 
 type SunDirectionFn = Box<dyn Fn(&Point2) -> Point2>;
 
@@ -59,12 +60,17 @@ impl Branch {
         }
     }
 
-    pub(crate) fn advance(&mut self, additional_resources: f32, sun_direction_fn: &SunDirectionFn) {
+    pub(crate) fn advance(
+        &mut self,
+        additional_resources: f32,
+        sun_direction_fn: &SunDirectionFn,
+        random: &mut Random,
+    ) {
         if additional_resources == 0.0 {
             return;
         }
         let accumulation_multiplier = if self.stopped { 0.05 } else { 1.0 };
-        let accumulate = random_range(
+        let accumulate = random.range(
             0.0,
             accumulation_multiplier * additional_resources
                 / (self.children.len() + 3).to_f32().unwrap(),
@@ -73,39 +79,43 @@ impl Branch {
         let mut remaining = additional_resources - accumulate;
 
         for c in self.children.iter_mut().rev() {
-            let chunk = random_range(0.0, remaining);
-            c.advance(chunk, sun_direction_fn);
+            let chunk = random.range(0.0, remaining);
+            c.advance(chunk, sun_direction_fn, random);
             remaining = remaining - chunk;
         }
         self.resources += accumulate + remaining;
 
         if self.resources > NEW_BRANCH_RESOURCES_LEVEL {
-            if random_range(0.0, 1.0) < 0.25 {
-                self.resources = self.resources - random_range(BRANCH_COST_MIN, BRANCH_COST_MAX);
+            if random.range(0.0, 1.0) < 0.25 {
+                self.resources = self.resources - random.range(BRANCH_COST_MIN, BRANCH_COST_MAX);
                 let new_branch_direction = self
                     .growth_direction
-                    .rotate(PI * if random::<bool>() { 0.5 } else { -0.5 });
+                    .rotate(PI * if random.random::<bool>() { 0.5 } else { -0.5 });
                 let sun_direction = (sun_direction_fn(&self.tip) - self.tip).normalize();
                 let combined_direction = (2.25 * new_branch_direction + sun_direction)
                     .normalize()
-                    .rotate(PI * random_range(-0.05, 0.05));
-                let new_branch = Branch::new(self.tip, combined_direction, child_color(self.color));
+                    .rotate(PI * random.range(-0.05, 0.05));
+                let new_branch = Branch::new(
+                    self.tip,
+                    combined_direction,
+                    child_color(self.color, random),
+                );
                 self.children.push(new_branch);
             }
         }
         if !self.stopped && self.resources > GROWTH_MIN_RESOURCES_LEVEL {
-            if random_range(0.0, 1.0) < 0.15 {
+            if random.range(0.0, 1.0) < 0.15 {
                 let sun_direction = (sun_direction_fn(&self.tip) - self.tip).normalize();
-                if sun_direction != self.growth_direction && random_range(0.0, 1.0) < 0.25 {
+                if sun_direction != self.growth_direction && random.range(0.0, 1.0) < 0.25 {
                     self.stopped = true;
                     let growth = self.resources;
                     self.resources = 0.0;
                     let new_direction = self.growth_direction + sun_direction;
                     let mut continuation = Branch::new(self.tip, new_direction, self.color);
-                    continuation.advance(growth, sun_direction_fn);
+                    continuation.advance(growth, sun_direction_fn, random);
                     self.children.push(continuation);
                 } else {
-                    let growth = random_range(1.0, 0.5 * GROWTH_MIN_RESOURCES_LEVEL);
+                    let growth = random.range(1.0, 0.5 * GROWTH_MIN_RESOURCES_LEVEL);
                     self.resources = self.resources - growth;
                     self.tip = self.tip + growth * 4.0 * self.growth_direction;
                 }
@@ -125,10 +135,10 @@ impl Branch {
     }
 }
 
-fn child_color(color: Hsl) -> Hsl {
+fn child_color(color: Hsl, random: &mut Random) -> Hsl {
     let hue = color.hue;
     let hue_rad = hue.to_radians();
-    let hue_rad = hue_rad + PI * random_range(-0.15, 0.15);
+    let hue_rad = hue_rad + PI * random.range(-0.15, 0.15);
     let hue = hue_rad.to_degrees();
     Hsl::new(hue, color.saturation, color.lightness)
 }
@@ -0,0 +1,68 @@
+use nannou::prelude::*;
+use sketch_runtime::{Random, Sketch};
+
+use crate::model::*;
+
+const UPDATE_DELAY: u128 = 1200;
+
+impl Sketch for Model {
+    const TITLE: &'static str = "art4-trees";
+
+    /// creates a initial [Model] instance.
+    fn model(app: &App, random: &mut Random) -> Self {
+        let window = app.window_rect();
+        let h = window.h();
+        let sd = Box::new(move |point: &Point2| vec2(point.x, h));
+        Model {
+            height: window.h(),
+            width: window.w(),
+            mouse_position: None,
+            delay: 0,
+            tree: Tree::new(
+                window.mid_bottom() + vec2(0.0, 1.0),
+                Hsl::new(random.range(100.0, 140.0), 1.0, 0.5),
+            ),
+            sun_direction: sd,
+        }
+    }
+
+    /// Model update
+    fn update(&mut self, _app: &App, random: &mut Random, update: Update) {
+        // the update times, rather than the app ones, stay steady while recording
+        let delay = update.since_last.as_millis() + self.delay;
+
+        if delay > UPDATE_DELAY {
+            let trunk: &mut Branch = &mut self.tree.trunk;
+            trunk.advance(
+                1.0 * update.since_start.as_secs_f32(),
+                &self.sun_direction,
+                random,
+            );
+        } else {
+            self.delay = delay;
+        }
+    }
+
+    fn view(&self, app: &App, frame: Frame) {
+        if app.elapsed_frames() == 0 {
+            frame.clear(BLACK);
+        }
+
+        let draw = app.draw();
+        self.tree.trunk.draw(&draw);
+
+        // put everything on the frame
+        draw.to_frame(app, &frame).unwrap()
+    }
+
+    /// Event handler
+    fn event(&mut self, _app: &App, _random: &mut Random, event: WindowEvent) {
+        match event {
+            MouseMoved(pos) => self.mouse_position = Some(pos),
+            MousePressed(_) => {
+                println!("Mouse pressed at {:?}", self.mouse_position);
+            }
+            _ => (),
+        }
+    }
+}
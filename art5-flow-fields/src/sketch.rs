@@ -1,6 +1,8 @@
-use nannou::noise::{NoiseFn, Perlin};
+use nannou::color::IntoLinSrgba;
+use nannou::noise::{NoiseFn, Perlin, Seedable};
 use nannou::prelude::*;
-use nannou::wgpu::{Backends, DeviceDescriptor, Limits};
+use shape_builder::{ColoredMesh, ShapeSink};
+use sketch_runtime::{Headless, Random, Sketch};
 
 #[derive(Debug)]
 pub struct Triangle {
@@ -19,193 +21,204 @@ pub struct Model {
     pub triangles: Vec<Triangle>,
 }
 
-pub async fn run_app() {
-    app::Builder::new_async(|app| {
-        Box::new(async move {
-            create_window(app).await;
-            model(app)
-        })
-    })
-    .backends(Backends::PRIMARY | Backends::GL)
-    .update(update)
-    .run_async()
-    .await;
-}
+impl Sketch for Model {
+    const TITLE: &'static str = "art5-flow-fields";
 
-async fn create_window(app: &App) {
-    let device_desc = DeviceDescriptor {
-        limits: Limits {
-            max_texture_dimension_2d: 8192,
-            ..Limits::downlevel_webgl2_defaults()
-        },
-        ..Default::default()
-    };
-
-    app.new_window()
-        .device_descriptor(device_desc)
-        .title("art5-flow-fields")
-        .event(event)
-        // .key_pressed(key_pressed)
-        // .key_released(key_released)
-        // .mouse_pressed(mouse_pressed)
-        // .mouse_moved(mouse_moved)
-        // .mouse_released(mouse_released)
-        // .mouse_wheel(mouse_wheel)
-        // .touch(touch)
-        .view(view)
-        .build_async()
-        .await
-        .unwrap();
-}
+    /// creates a initial [Model] instance.
+    fn model(app: &App, random: &mut Random) -> Self {
+        Model::canvas_model(app.window_rect().wh(), random)
+    }
 
-/// creates a initial [Model] instance.
-fn model(app: &App) -> Model {
-    let window = app.window_rect();
-
-    let noise = Perlin::default();
-
-    let w = window.w() * 0.5 * 1.2;
-    let h = window.h() * 0.5 * 1.2;
-    let triangle_side = 20.0;
-    let mut triangles = Vec::new();
-    let mut i = -w;
-    while i <= w {
-        let mut j = -h;
-        while j <= h {
-            let a = pt2(i, j);
-            let b = pt2(i + triangle_side, j);
-            let c = pt2(i, j + triangle_side);
-            let color = hsl(
-                map_range(
-                    NoiseFn::get(
-                        &noise,
-                        [
-                            (i + random_range(0.15,0.25) * triangle_side).to_f64().unwrap() * 0.01,
-                            (j + random_range(0.15,0.25) * triangle_side).to_f64().unwrap() * 0.01,
-                            0.3,
-                        ],
-                    )
-                    .to_f32()
-                    .unwrap(),
-                    -1.0,
-                    1.0,
-                    0.0,
-                    1.0,
-                ),
-                0.7,
-                0.1,
-            );
-            triangles.push(Triangle { a, b, c, color });
-
-            let a = pt2(i + triangle_side, j + triangle_side);
-            let b = pt2(i + triangle_side, j);
-            let c = pt2(i, j + triangle_side);
-            let color = hsl(
-                map_range(
-                    NoiseFn::get(
-                        &noise,
-                        [
-                            (i + random_range(0.75,0.85) * triangle_side).to_f64().unwrap() * 0.01,
-                            (j + random_range(0.75,0.85) * triangle_side).to_f64().unwrap() * 0.01,
-                            0.7,
-                        ],
-                    )
-                    .to_f32()
-                    .unwrap(),
-                    -1.0,
-                    1.0,
-                    0.0,
-                    1.0,
-                ),
-                0.7,
-                0.7,
-            );
-            triangles.push(Triangle { a, b, c, color });
-            j += triangle_side;
-        }
-        i += triangle_side;
+    /// Model update
+    fn update(&mut self, app: &App, random: &mut Random, _update: Update) {
+        self.step(app.elapsed_frames(), random);
     }
 
-    Model {
-        height: window.h(),
-        width: window.w(),
-        mouse_position: None,
-        noise,
-        triangles,
+    fn view(&self, app: &App, frame: Frame) {
+        let mut draw = app.draw();
+        self.draw(frame.nth(), &mut draw);
+
+        draw.text(&format!("f: {}", app.fps()))
+            .font_size(32)
+            .color(BLACK)
+            .align_text_top()
+            .y(self.height * 0.5)
+            .finish();
+        // put everything on the frame
+        draw.to_frame(app, &frame).unwrap()
     }
-}
 
-/// Model update
-fn update(_app: &App, model: &mut Model, _update: Update) {
-
-    let compute_delta = |p: Point2|{
-        
-        let x = map_range(
-            NoiseFn::get(
-                &model.noise,
-                [
-                    p.x.to_f64().unwrap() * 0.01,
-                    p.y.to_f64().unwrap() * 0.01,
-                    0.0,
-                ],
-            )
-            .to_f32()
-            .unwrap(),
-            -1.0,
-            1.0,
-            -0.1,
-            0.1,
-        );
-        let y = map_range(
-            NoiseFn::get(
-                &model.noise,
-                [
-                    p.x.to_f64().unwrap() * 0.01,
-                    p.y.to_f64().unwrap() * 0.01,
-                    1.0,
-                ],
-            )
-            .to_f32()
-            .unwrap(),
-            -1.0,
-            1.0,
-            -0.1,
-            0.1,
-        );
-        pt2(x, y)
-    };
-
-    for t in model.triangles.iter_mut() {
-        t.a += compute_delta(t.a);
-        t.b += compute_delta(t.b);
-        t.c += compute_delta(t.c);
+    /// Event handler
+    fn event(&mut self, _app: &App, _random: &mut Random, event: WindowEvent) {
+        match event {
+            MouseMoved(pos) => self.mouse_position = Some(pos),
+            MousePressed(_) => {}
+            _ => (),
+        }
     }
 }
 
-fn view(app: &App, model: &Model, frame: Frame) {
-    frame.clear(WHITE);
+impl Headless for Model {
+    fn canvas_model(size: Vec2, random: &mut Random) -> Self {
+        let noise = Perlin::new().set_seed(random.noise_seed());
+
+        let w = size.x * 0.5 * 1.2;
+        let h = size.y * 0.5 * 1.2;
+        let triangle_side = 20.0;
+        let mut triangles = Vec::new();
+        let mut i = -w;
+        while i <= w {
+            let mut j = -h;
+            while j <= h {
+                let a = pt2(i, j);
+                let b = pt2(i + triangle_side, j);
+                let c = pt2(i, j + triangle_side);
+                let color = hsl(
+                    map_range(
+                        NoiseFn::get(
+                            &noise,
+                            [
+                                (i + random.range(0.15, 0.25) * triangle_side)
+                                    .to_f64()
+                                    .unwrap()
+                                    * 0.01,
+                                (j + random.range(0.15, 0.25) * triangle_side)
+                                    .to_f64()
+                                    .unwrap()
+                                    * 0.01,
+                                0.3,
+                            ],
+                        )
+                        .to_f32()
+                        .unwrap(),
+                        -1.0,
+                        1.0,
+                        0.0,
+                        1.0,
+                    ),
+                    0.7,
+                    0.1,
+                );
+                triangles.push(Triangle { a, b, c, color });
+
+                let a = pt2(i + triangle_side, j + triangle_side);
+                let b = pt2(i + triangle_side, j);
+                let c = pt2(i, j + triangle_side);
+                let color = hsl(
+                    map_range(
+                        NoiseFn::get(
+                            &noise,
+                            [
+                                (i + random.range(0.75, 0.85) * triangle_side)
+                                    .to_f64()
+                                    .unwrap()
+                                    * 0.01,
+                                (j + random.range(0.75, 0.85) * triangle_side)
+                                    .to_f64()
+                                    .unwrap()
+                                    * 0.01,
+                                0.7,
+                            ],
+                        )
+                        .to_f32()
+                        .unwrap(),
+                        -1.0,
+                        1.0,
+                        0.0,
+                        1.0,
+                    ),
+                    0.7,
+                    0.7,
+                );
+                triangles.push(Triangle { a, b, c, color });
+                j += triangle_side;
+            }
+            i += triangle_side;
+        }
 
-    let draw = app.draw();
+        Model {
+            height: size.y,
+            width: size.x,
+            mouse_position: None,
+            noise,
+            triangles,
+        }
+    }
 
-    for t in &model.triangles {
-        draw.tri().color(t.color).points(t.a, t.b, t.c).finish();
+    fn step(&mut self, _frame: u64, _random: &mut Random) {
+        let compute_delta = |p: Point2| {
+            let x = map_range(
+                NoiseFn::get(
+                    &self.noise,
+                    [
+                        p.x.to_f64().unwrap() * 0.01,
+                        p.y.to_f64().unwrap() * 0.01,
+                        0.0,
+                    ],
+                )
+                .to_f32()
+                .unwrap(),
+                -1.0,
+                1.0,
+                -0.1,
+                0.1,
+            );
+            let y = map_range(
+                NoiseFn::get(
+                    &self.noise,
+                    [
+                        p.x.to_f64().unwrap() * 0.01,
+                        p.y.to_f64().unwrap() * 0.01,
+                        1.0,
+                    ],
+                )
+                .to_f32()
+                .unwrap(),
+                -1.0,
+                1.0,
+                -0.1,
+                0.1,
+            );
+            pt2(x, y)
+        };
+
+        for t in self.triangles.iter_mut() {
+            t.a += compute_delta(t.a);
+            t.b += compute_delta(t.b);
+            t.c += compute_delta(t.c);
+        }
     }
 
-    draw.text(&format!("f: {}", app.fps()))
-        .font_size(32)
-        .color(BLACK)
-        .align_text_top()
-        .y(model.height * 0.5)
-        .finish();
-    // put everything on the frame
-    draw.to_frame(app, &frame).unwrap()
+    /// Draws all the triangles as one mesh.
+    fn draw(&self, _frame: u64, sink: &mut dyn ShapeSink) {
+        sink.clear(WHITE.into_lin_srgba());
+
+        let mut mesh = ColoredMesh {
+            vertices: Vec::with_capacity(self.triangles.len() * 3),
+            indices: (0..self.triangles.len() * 3).collect(),
+        };
+        for t in &self.triangles {
+            let color = t.color.into_lin_srgba();
+            mesh.vertices
+                .extend([(t.a, color), (t.b, color), (t.c, color)]);
+        }
+        sink.fill_mesh(&mesh);
+    }
 }
 
-/// Event handler
-fn event(_app: &App, model: &mut Model, event: WindowEvent) {
-    match event {
-        MouseMoved(pos) => model.mouse_position = Some(pos),
-        MousePressed(_) => {}
-        _ => (),
+#[cfg(test)]
+mod test {
+    use sketch_runtime::{check_golden, HeadlessRun};
+
+    use crate::sketch::Model;
+
+    #[test]
+    fn test_golden_flow_field() {
+        let mut run = HeadlessRun::<Model>::new(160, 120, 42);
+        for _ in 0..10 {
+            run.next_frame();
+        }
+        let golden = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/flow_field.png");
+        check_golden(&run.canvas().to_image(), golden).unwrap();
     }
 }
@@ -1,10 +1,14 @@
 use nannou::color::{Alpha, IntoLinSrgba};
-use nannou::draw::primitive::path::DrawingPath;
 use nannou::draw::properties::ColorScalar;
-use nannou::draw::Drawing;
+use nannou::glam::Affine2;
 use nannou::prelude::*;
-use nannou::wgpu::{Backends, DeviceDescriptor, Limits};
-use shape_builder::{ShapeBuilder, ShapeBuilderFactory, ShapePath};
+use shape_builder::{
+    ColoredMesh, DrawShape, FillRule, Flattening, Gradient, Path, PathBuilder, Shape, ShapeBuilder,
+    ShapeError, TransformShape,
+};
+use sketch_runtime::{Random, Sketch};
+
+const PETAL_FLATTENING: Flattening = Flattening::Tolerance(0.25);
 
 trait DrawSelf {
     fn draw_self(&self, draw: &Draw, scale: f32, rotation: f32);
@@ -12,6 +16,28 @@ trait DrawSelf {
     fn born_time(&self) -> u64 {
         0
     }
+
+    /// Whether `point` is on the shape drawn with the same `scale` and `rotation`.
+    fn contains(&self, _point: Vec2, _scale: f32, _rotation: f32) -> bool {
+        false
+    }
+
+    fn set_color(&mut self, _color: Alpha<Hsl, f32>) {}
+}
+
+/// Scale and rotation of a shape `frames` frames after it was born.
+fn animation(frames: u64) -> (f32, f32) {
+    let t = frames.to_f32().unwrap() * 0.1;
+    (1.0 + t * 0.01, PI * 0.015 * t)
+}
+
+fn random_color(random: &mut Random, lightness: (f32, f32), alpha: f32) -> Alpha<Hsl, f32> {
+    hsla(
+        random.range(0.0, 360.0),
+        random.range(0.4, 0.7),
+        random.range(lightness.0, lightness.1),
+        alpha,
+    )
 }
 
 pub struct Flower {
@@ -22,46 +48,109 @@ pub struct Flower {
     pub color: Alpha<Hsl, f32>,
     pub leaf_count: u32,
     pub born_time: u64,
+    /// Petals at their initial size and angle, only moved around by the draw
+    /// transforms as the flower grows.
+    shape: Shape,
+    fill: ColoredMesh,
+}
+
+impl Flower {
+    pub fn new(
+        start: Vec2,
+        tip: Vec2,
+        middle: f32,
+        thickness: f32,
+        color: Alpha<Hsl, f32>,
+        leaf_count: u32,
+        born_time: u64,
+    ) -> Result<Self, ShapeError> {
+        let path = flower_path(leaf_count, 1.0, 0.0, tip - start, middle, thickness)?;
+        let shape = Shape::new(path, PETAL_FLATTENING, FillRule::NonZero);
+        let fill = shape.fill_gradient(&flower_gradient((tip - start).length(), color));
+        Ok(Flower {
+            start,
+            tip,
+            middle,
+            thickness,
+            color,
+            leaf_count,
+            born_time,
+            shape,
+            fill,
+        })
+    }
 }
 
 impl DrawSelf for Flower {
     fn draw_self(&self, draw: &Draw, scale: f32, rotation: f32) {
-        let color = IntoLinSrgba::into_lin_srgba(self.color);
-
-        draw_simple_flower(
-            &draw,
-            self.leaf_count,
-            scale,
-            rotation,
-            self.start,
-            self.tip,
-            self.middle,
-            self.thickness,
-            color,
-        );
+        draw.xy(self.start)
+            .rotate(rotation)
+            .scale(scale)
+            .colored_mesh(&self.fill)
+            .finish();
     }
 
     fn born_time(&self) -> u64 {
         self.born_time
     }
+
+    fn contains(&self, point: Vec2, scale: f32, rotation: f32) -> bool {
+        let drawn = Affine2::from_scale_angle_translation(Vec2::splat(scale), rotation, self.start);
+        let point = drawn.inverse().transform_point2(point);
+        self.shape.path().contains(point, FillRule::NonZero)
+    }
+
+    fn set_color(&mut self, color: Alpha<Hsl, f32>) {
+        self.color = color;
+        let radius = (self.tip - self.start).length();
+        self.fill = self.shape.fill_gradient(&flower_gradient(radius, color));
+    }
 }
 
 pub struct Circle {
     pub center: Vec2,
     pub radius: f32,
     pub color: Alpha<Hsl, f32>,
+    shape: Shape,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f32, color: Alpha<Hsl, f32>) -> Result<Self, ShapeError> {
+        let radii = Vec2::splat(radius);
+        let shape = PathBuilder::new(vec2(radius, 0.0))?
+            .arc_to(radii, 0.0, false, true, vec2(-radius, 0.0))?
+            .arc_to(radii, 0.0, false, true, vec2(radius, 0.0))?
+            .close()
+            .with_flattening(PETAL_FLATTENING)?
+            .into();
+        Ok(Circle {
+            center,
+            radius,
+            color,
+            shape,
+        })
+    }
 }
+
 impl DrawSelf for Circle {
     fn draw_self(&self, draw: &Draw, scale: f32, _: f32) {
-        draw.ellipse()
-            .xy(self.center)
-            .radius(self.radius * scale)
+        draw.xy(self.center)
+            .scale(scale)
+            .shape_fill(&self.shape)
             .color(self.color)
             .finish();
     }
+
+    fn contains(&self, point: Vec2, scale: f32, _: f32) -> bool {
+        point.distance(self.center) <= self.radius * scale
+    }
+
+    fn set_color(&mut self, color: Alpha<Hsl, f32>) {
+        self.color = color;
+    }
 }
 
-struct Model {
+pub struct Model {
     pub height: f32,
     pub width: f32,
     pub mouse_position: Option<Point2>,
@@ -71,229 +160,132 @@ struct Model {
     pub pressed: bool,
 }
 
-pub async fn run_app() {
-    app::Builder::new_async(|app| {
-        Box::new(async move {
-            create_window(app).await;
-            model(app)
-        })
-    })
-    .backends(Backends::PRIMARY | Backends::GL)
-    .update(update)
-    // .loop_mode(LoopMode::Wait)
-    .run_async()
-    .await;
-}
+impl Sketch for Model {
+    const TITLE: &'static str = "art6-flowers";
 
-async fn create_window(app: &App) {
-    let device_desc = DeviceDescriptor {
-        limits: Limits {
-            max_texture_dimension_2d: 8192,
-            ..Limits::downlevel_webgl2_defaults()
-        },
-        ..Default::default()
-    };
-
-    app.new_window()
-        .device_descriptor(device_desc)
-        .title("art6-flowers")
-        .event(event)
-        // .key_pressed(key_pressed)
-        // .key_released(key_released)
-        // .mouse_pressed(mouse_pressed)
-        // .mouse_moved(mouse_moved)
-        // .mouse_released(mouse_released)
-        // .mouse_wheel(mouse_wheel)
-        // .touch(touch)
-        .view(view)
-        .build_async()
-        .await
-        .unwrap();
-}
+    /// creates a initial [Model] instance.
+    fn model(app: &App, _random: &mut Random) -> Self {
+        let window = app.window_rect();
 
-/// creates a initial [Model] instance.
-fn model(app: &App) -> Model {
-    let window = app.window_rect();
-
-    Model {
-        height: window.h(),
-        width: window.w(),
-        mouse_position: None,
-        flowers: vec![],
-        last_flower_born_time: None,
-        pressed: false,
+        Model {
+            height: window.h(),
+            width: window.w(),
+            mouse_position: None,
+            flowers: vec![],
+            last_flower_born_time: None,
+            pressed: false,
+        }
     }
-}
-
-/// Model update
-fn update(_app: &App, model: &mut Model, _update: Update) {}
 
-fn view(app: &App, model: &Model, frame: Frame) {
-    let draw = app.draw();
+    fn view(&self, app: &App, frame: Frame) {
+        let draw = app.draw();
 
-    frame.clear(WHITESMOKE);
-    
-    for f in model.flowers.iter() {
-        let t = (app.elapsed_frames() - f.born_time()).to_f32().unwrap() * 0.1;
+        frame.clear(WHITESMOKE);
 
-        let rotation = PI * 0.015 * t;
-        let scale = 1.0 + t * 0.01;
+        for f in self.flowers.iter() {
+            let (scale, rotation) = animation(app.elapsed_frames() - f.born_time());
+            f.draw_self(&draw, scale, rotation);
+        }
 
-        f.draw_self(&draw, scale, rotation);
+        // put everything on the frame
+        draw.to_frame(app, &frame).unwrap()
     }
 
-    // put everything on the frame
-    draw.to_frame(app, &frame).unwrap()
+    /// Event handler
+    fn event(&mut self, app: &App, random: &mut Random, event: WindowEvent) {
+        let t = app.elapsed_frames();
+        match event {
+            MouseMoved(pos) => {
+                self.mouse_position = Some(pos);
+                if self.pressed && self.last_flower_born_time.map_or(true, |b| b + 12 < t) {
+                    let tip = pos + vec2(10.0 * random.range(3.0, 8.0), 0.0);
+                    let flower = Flower::new(
+                        pos,
+                        tip,
+                        random.range(0.0, 1.0),
+                        random.range(10.0, 40.0),
+                        random_color(random, (0.2, 0.5), 0.85),
+                        random.range(3, 10) * 2,
+                        t,
+                    );
+                    // a degenerate flower, e.g. with a flat petal, is simply not planted
+                    if let Ok(flower) = flower {
+                        self.flowers.push(Box::new(flower));
+                    }
+                    if random.random::<bool>() {
+                        let circle = Circle::new(
+                            pos,
+                            pos.distance(tip) * 0.15,
+                            random_color(random, (0.3, 0.5), 0.95),
+                        );
+                        if let Ok(circle) = circle {
+                            self.flowers.push(Box::new(circle));
+                        }
+                    }
+                    self.last_flower_born_time = Some(t);
+                }
+            }
+            MousePressed(_) => {
+                // clicking on a shape recolors the topmost one, anywhere else starts planting
+                let clicked = self.mouse_position.and_then(|pos| {
+                    self.flowers.iter_mut().rev().find(|f| {
+                        let (scale, rotation) = animation(t - f.born_time());
+                        f.contains(pos, scale, rotation)
+                    })
+                });
+                match clicked {
+                    Some(shape) => shape.set_color(random_color(random, (0.2, 0.5), 0.85)),
+                    None => self.pressed = true,
+                }
+            }
+            MouseReleased(_) => {
+                self.pressed = false;
+            }
+            _ => (),
+        }
+    }
 }
 
-fn draw_simple_flower(
-    draw: &Draw,
+/// Outline of all the petals of a flower centered at the origin.
+fn flower_path(
     leaf_count: u32,
     scale: f32,
     rotation: f32,
-    start: Vec2,
     tip: Vec2,
     middle: f32,
     thickness: f32,
-    color: impl IntoLinSrgba<ColorScalar>,
-) {
-    let color = color.into_lin_srgba();
-    let tip_translated = tip - start;
-    let m = (tip_translated) * middle;
+) -> Result<Path, ShapeError> {
+    let m = tip * middle;
     let m1 = m + vec2(0.0, thickness * 0.5);
     let m2 = m + vec2(0.0, thickness * -0.5);
     let root = vec2(0.0, 0.0);
-    let draw = draw.xy(start);
-    for i in 0..leaf_count {
-        draw.path()
-            .start_shape(root)
-            .add_bezier_curve(vec![m1 * scale, tip_translated * scale])
-            .add_bezier_curve(vec![root])
-            .add_bezier_curve(vec![m2 * scale, tip_translated * scale])
-            .add_bezier_curve(vec![root])
-            .as_fill()
-            .rotate(i.to_f32().unwrap() * TAU / (leaf_count.to_f32().unwrap()) + rotation)
-            .color(color)
-            .finish();
-    }
+    let petal = PathBuilder::new(root)?
+        .quadratic_to(m1, tip)?
+        .line_to(root)?
+        .quadratic_to(m2, tip)?
+        .close()
+        .build()
+        .scale(scale);
+    // union the petals, so that overlapping ones don't show seams when blended
+    let petals = (0..leaf_count)
+        .map(|i| {
+            let angle = i.to_f32().unwrap() * TAU / (leaf_count.to_f32().unwrap()) + rotation;
+            petal.clone().rotate(angle)
+        })
+        .collect::<Vec<_>>();
+    Ok(Path::union_all(&petals, PETAL_FLATTENING))
 }
 
-fn draw_duo_colored_flower(
-    draw: &Draw,
-    leaf_count: u32,
-    scale: f32,
-    rotation: f32,
-    start: Vec2,
-    tip: Vec2,
-    middle: f32,
-    thickness: f32,
-    color: &[impl IntoLinSrgba<ColorScalar> + Clone; 2],
-) {
-    draw_simple_flower(
-        draw,
-        leaf_count,
-        scale,
-        rotation,
-        start,
-        tip,
-        middle,
-        thickness,
-        color[0].clone(),
-    );
-    draw_simple_flower(
-        draw,
-        leaf_count,
-        scale,
-        rotation,
-        start,
-        tip * 0.85,
-        middle,
-        thickness * 0.75,
-        color[1].clone(),
+/// Petals fade from their color at the center to a lighter tip.
+fn flower_gradient(radius: f32, color: impl IntoLinSrgba<ColorScalar>) -> Gradient {
+    let color = color.into_lin_srgba();
+    let tip_color = lin_srgba(
+        0.5 + color.red * 0.5,
+        0.5 + color.green * 0.5,
+        0.5 + color.blue * 0.5,
+        color.alpha,
     );
-}
-
-/// Event handler
-fn event(_app: &App, model: &mut Model, event: WindowEvent) {
-    let t = _app.elapsed_frames();
-    match event {
-        MouseMoved(pos) => {
-            model.mouse_position = Some(pos);
-            if model.pressed && model.last_flower_born_time.map_or(true, |b| b + 12 < t) {
-                let tip = pos + vec2(10.0 * random_range(3.0, 8.0), 0.0);
-                let flower = Flower {
-                    start: pos,
-                    tip: tip,
-                    middle: random_range(0.0, 1.0),
-                    thickness: random_range(10.0, 40.0),
-                    color: hsla(
-                        random_range(0.0, 360.0),
-                        random_range(0.4, 0.7),
-                        random_range(0.2, 0.5),
-                        0.85,
-                    ),
-                    leaf_count: random_range(3, 10) * 2,
-                    born_time: t,
-                };
-                model.flowers.push(Box::new(flower));
-                if random::<bool>() {
-                    let circle = Circle {
-                        center: pos,
-                        radius: pos.distance(tip) * 0.15,
-                        color:hsla(
-                            random_range(0.0, 360.0),
-                            random_range(0.4, 0.7),
-                            random_range(0.3, 0.5),
-                            0.95,
-                        )
-                    };
-                    model.flowers.push(Box::new(circle));
-                }
-                model.last_flower_born_time = Some(t);
-            }
-        }
-        MousePressed(_) => {
-            model.pressed = true;
-        }
-        MouseReleased(_) => {
-            model.pressed = false;
-        }
-        _ => (),
-    }
-}
-trait QuadraticBezierCurveDraw<'a> {
-    fn quadratic_bezier(self, points: &'a [Vec2; 3]) -> DrawingPath;
-}
-
-impl<'a> QuadraticBezierCurveDraw<'a> for Drawing<'a, nannou::draw::primitive::PathStroke> {
-    fn quadratic_bezier(self, points: &'a [Vec2; 3]) -> DrawingPath {
-        let points = (0..11)
-            .map(|t_delta| t_delta.to_f32().unwrap() * 0.1)
-            .map(|td| {
-                (1.0 - td).pow(2) * points[0]
-                    + 2.0 * (1.0 - td) * td * points[1]
-                    + td.pow(2) * points[2]
-            });
-        self.points(points)
-    }
-}
-
-fn leaf_points(
-    start: Vec2,
-    tip: Vec2,
-    thickness: f32,
-    middle: f32,
-) -> impl IntoIterator<Item = Vec2> {
-    let divisions = 20;
-    let delta = 1.0 / divisions as f32;
-    let m = (start + tip) * middle;
-    let p1 = vec2(m.x + thickness * 0.5, m.y);
-    let p2 = vec2(m.x + thickness * -0.5, m.y);
-    let points1 = (0..(divisions + 1))
-        .map(|t_delta| t_delta.to_f32().unwrap() * delta)
-        .map(|td| (1.0 - td).pow(2) * start + 2.0 * (1.0 - td) * td * p1 + td.pow(2) * tip);
-    let points2 = (0..(divisions + 1))
-        .map(|t_delta| t_delta.to_f32().unwrap() * delta)
-        .map(|td| (1.0 - td).pow(2) * tip + 2.0 * (1.0 - td) * td * p2 + td.pow(2) * start);
-    points1.chain(points2).collect::<Vec<_>>()
+    Gradient::radial(vec2(0.0, 0.0), radius)
+        .with_stop(0.0, color)
+        .with_stop(1.0, tip_color)
 }
@@ -0,0 +1,141 @@
+use std::f32::consts::TAU;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use nannou::color::LinSrgba;
+use nannou::glam::{vec2, Vec2};
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+use shape_builder::{
+    BezierCurve, FillRule, Flattening, Gradient, Path, PathBuilder, Shape, ShapeBuilder,
+    ShapeError, TransformShape,
+};
+
+/// Roughly the number of curves a busy frame of `art6-flowers` flattens.
+const CURVES: usize = 1000;
+const PETAL_FLATTENING: Flattening = Flattening::Tolerance(0.25);
+/// Flowers on screen after a while of planting in `art6-flowers`.
+const FLOWERS: usize = 200;
+
+fn random_cubics(count: usize) -> Vec<BezierCurve<Vec2>> {
+    let mut rng = StdRng::seed_from_u64(17);
+    let mut point = || vec2(rng.gen_range(-500.0..500.0), rng.gen_range(-500.0..500.0));
+    (0..count)
+        .map(|_| BezierCurve::new(vec![point(), point(), point(), point()]).unwrap())
+        .collect()
+}
+
+fn petal() -> Result<Path, ShapeError> {
+    let tip = vec2(60.0, 0.0);
+    Ok(PathBuilder::new(Vec2::ZERO)?
+        .quadratic_to(vec2(30.0, 12.0), tip)?
+        .line_to(Vec2::ZERO)?
+        .quadratic_to(vec2(30.0, -12.0), tip)?
+        .close()
+        .build())
+}
+
+/// Petals of a flower the way `art6-flowers` builds them.
+fn petals(leaf_count: u32) -> Vec<Path> {
+    let petal = petal().unwrap();
+    (0..leaf_count)
+        .map(|i| petal.clone().rotate(i as f32 * TAU / leaf_count as f32))
+        .collect()
+}
+
+fn flatten_cubics(c: &mut Criterion) {
+    let curves = random_cubics(CURVES);
+    let mut group = c.benchmark_group("flatten_1000_cubics");
+    for flattening in [Flattening::Divisions(10), Flattening::Tolerance(0.25)] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{:?}", flattening)),
+            &flattening,
+            |b, &flattening| {
+                b.iter(|| {
+                    curves
+                        .iter()
+                        .map(|curve| curve.flatten_with(black_box(flattening)).len())
+                        .sum::<usize>()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn flower(c: &mut Criterion) {
+    let petals = petals(16);
+    c.bench_function("flower_flatten", |b| {
+        b.iter(|| {
+            black_box(&petals)
+                .iter()
+                .map(|petal| petal.flatten(PETAL_FLATTENING).len())
+                .sum::<usize>()
+        })
+    });
+    c.bench_function("flower_union", |b| {
+        b.iter(|| Path::union_all(black_box(&petals), PETAL_FLATTENING))
+    });
+    let flower = Path::union_all(&petals, PETAL_FLATTENING);
+    c.bench_function("flower_fill_mesh", |b| {
+        b.iter(|| black_box(&flower).fill_mesh(PETAL_FLATTENING, FillRule::NonZero))
+    });
+}
+
+fn gradient(center: Vec2, radius: f32) -> Gradient {
+    Gradient::radial(center, radius)
+        .with_stop(0.0, LinSrgba::new(0.8, 0.2, 0.3, 0.85))
+        .with_stop(1.0, LinSrgba::new(0.9, 0.6, 0.65, 0.85))
+}
+
+/// One frame of growing flowers: rebuilding every flower compared to
+/// moving flowers cached as [Shape]s.
+fn flower_frame(c: &mut Criterion) {
+    let petals = petals(16);
+    let transforms = (0..FLOWERS)
+        .map(|i| {
+            let t = i as f32 * 0.1;
+            (1.0 + t * 0.01, 0.05 * t, vec2(i as f32, -(i as f32)))
+        })
+        .collect::<Vec<_>>();
+    let mut group = c.benchmark_group("flower_frame");
+    group.bench_function("rebuilt", |b| {
+        b.iter(|| {
+            for &(scale, rotation, position) in &transforms {
+                let moved = petals
+                    .iter()
+                    .map(|petal| petal.clone().scale(scale).rotate(rotation));
+                let flower = Path::union_all(&moved.collect::<Vec<_>>(), PETAL_FLATTENING);
+                let gradient = gradient(position, 60.0 * scale);
+                black_box(
+                    flower
+                        .translate(position)
+                        .fill_mesh(PETAL_FLATTENING, FillRule::NonZero)
+                        .with_gradient(&gradient),
+                );
+            }
+        })
+    });
+    let flower = Shape::new(
+        Path::union_all(&petals, PETAL_FLATTENING),
+        PETAL_FLATTENING,
+        FillRule::NonZero,
+    );
+    let fill = flower.fill_gradient(&gradient(Vec2::ZERO, 60.0));
+    group.bench_function("cached", |b| {
+        b.iter(|| {
+            for &(scale, rotation, position) in &transforms {
+                // what the transforms of nannou's `Draw` do with the vertices
+                black_box(
+                    fill.clone()
+                        .scale(scale)
+                        .rotate(rotation)
+                        .translate(position),
+                );
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, flatten_cubics, flower, flower_frame);
+criterion_main!(benches);
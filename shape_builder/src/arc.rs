@@ -0,0 +1,177 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use nannou::glam::{vec2, Vec2};
+
+/// Converts an SVG-style elliptical arc into cubic Bézier segments.
+///
+/// Follows the endpoint to center parametrization conversion from the SVG
+/// specification (appendix F.6). `x_axis_rotation` is given in radians.
+/// Every returned item holds the two control points and the end point
+/// of one cubic segment, each one spanning at most a quarter of the ellipse.
+pub(crate) fn arc_to_cubics(
+    from: Vec2,
+    radii: Vec2,
+    x_axis_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Vec2,
+) -> Vec<[Vec2; 3]> {
+    if from == to {
+        return vec![];
+    }
+    let mut rx = radii.x.abs();
+    let mut ry = radii.y.abs();
+    if rx == 0.0 || ry == 0.0 {
+        // degenerated ellipse is a straight line
+        return vec![[from, to, to]];
+    }
+
+    let (sin_phi, cos_phi) = x_axis_rotation.sin_cos();
+    let half_delta = (from - to) * 0.5;
+    let x1 = cos_phi * half_delta.x + sin_phi * half_delta.y;
+    let y1 = -sin_phi * half_delta.x + cos_phi * half_delta.y;
+
+    // scale radii up when they are too small to connect both points
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        let scale = lambda.sqrt();
+        rx *= scale;
+        ry *= scale;
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+    let cx1 = coefficient * rx * y1 / ry;
+    let cy1 = -coefficient * ry * x1 / rx;
+
+    let middle = (from + to) * 0.5;
+    let center = vec2(
+        cos_phi * cx1 - sin_phi * cy1 + middle.x,
+        sin_phi * cx1 + cos_phi * cy1 + middle.y,
+    );
+
+    let start_angle = vec2((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let end_angle = vec2((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    let theta = start_angle.y.atan2(start_angle.x);
+    let mut delta = end_angle.y.atan2(end_angle.x) - theta;
+    if sweep && delta < 0.0 {
+        delta += TAU;
+    } else if !sweep && delta > 0.0 {
+        delta -= TAU;
+    }
+
+    let ellipse_point = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        let p = vec2(rx * cos, ry * sin);
+        center + vec2(cos_phi * p.x - sin_phi * p.y, sin_phi * p.x + cos_phi * p.y)
+    };
+    let ellipse_derivative = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        let d = vec2(-rx * sin, ry * cos);
+        vec2(cos_phi * d.x - sin_phi * d.y, sin_phi * d.x + cos_phi * d.y)
+    };
+
+    // small margin keeps rounding errors from adding a segment to exact quarter arcs
+    let segments = (delta.abs() / FRAC_PI_2 - 1e-3).ceil().max(1.0) as usize;
+    let step = delta / segments as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let mut start = from;
+    (0..segments)
+        .map(|i| {
+            let a1 = theta + step * i as f32;
+            let a2 = a1 + step;
+            let end = if i + 1 == segments {
+                to
+            } else {
+                ellipse_point(a2)
+            };
+            let ctrl1 = start + ellipse_derivative(a1) * k;
+            let ctrl2 = end - ellipse_derivative(a2) * k;
+            start = end;
+            [ctrl1, ctrl2, end]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use nannou::glam::{vec2, Vec2};
+
+    use super::arc_to_cubics;
+    use crate::BezierCurve;
+
+    fn cubics(from: Vec2, segments: &[[Vec2; 3]]) -> Vec<BezierCurve<Vec2>> {
+        let mut start = from;
+        segments
+            .iter()
+            .map(|[c1, c2, end]| {
+                let curve = BezierCurve::from_points(vec![start, *c1, *c2, *end]);
+                start = *end;
+                curve
+            })
+            .collect()
+    }
+
+    fn assert_on_circle(curves: &[BezierCurve<Vec2>], center: Vec2, radius: f32) {
+        for curve in curves {
+            for i in 0..=10 {
+                let d = curve.point_at(i as f32 * 0.1).distance(center);
+                assert!((d - radius).abs() < radius * 1e-3, "{} != {}", d, radius);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quarter_circle() {
+        let from = vec2(100.0, 0.0);
+        let segments = arc_to_cubics(from, vec2(100.0, 100.0), 0.0, false, true, vec2(0.0, 100.0));
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0][2], vec2(0.0, 100.0));
+        assert_on_circle(&cubics(from, &segments), Vec2::ZERO, 100.0);
+    }
+
+    #[test]
+    fn test_large_arc() {
+        let from = vec2(100.0, 0.0);
+        let segments = arc_to_cubics(from, vec2(100.0, 100.0), 0.0, true, false, vec2(0.0, 100.0));
+        assert_eq!(segments.len(), 3);
+        let curves = cubics(from, &segments);
+        assert_on_circle(&curves, Vec2::ZERO, 100.0);
+        // clockwise the long way around passes through the bottom of the circle
+        assert!(curves[1].point_at(0.5).distance(vec2(-70.71, -70.71)) < 0.1);
+    }
+
+    #[test]
+    fn test_too_small_radii_are_scaled() {
+        let from = vec2(-50.0, 0.0);
+        let segments = arc_to_cubics(from, vec2(1.0, 1.0), 0.0, false, true, vec2(50.0, 0.0));
+        assert_eq!(segments.len(), 2);
+        assert_on_circle(&cubics(from, &segments), Vec2::ZERO, 50.0);
+    }
+
+    #[test]
+    fn test_rotated_ellipse() {
+        let from = vec2(0.0, 0.0);
+        let to = vec2(0.0, 100.0);
+        let segments = arc_to_cubics(from, vec2(50.0, 20.0), FRAC_PI_2, false, true, to);
+        let curves = cubics(from, &segments);
+        assert_eq!(curves.last().unwrap().end(), to);
+        // major axis is vertical, so the arc bulges by the minor radius only
+        let middle = curves[0].end();
+        assert!((middle.x.abs() - 20.0).abs() < 0.1, "{:?}", middle);
+    }
+
+    #[test]
+    fn test_degenerated_arcs() {
+        let from = vec2(10.0, 10.0);
+        assert!(arc_to_cubics(from, vec2(5.0, 5.0), 0.0, false, false, from).is_empty());
+        assert_eq!(
+            arc_to_cubics(from, vec2(0.0, 5.0), 0.0, false, false, vec2(20.0, 10.0)),
+            vec![[from, vec2(20.0, 10.0), vec2(20.0, 10.0)]]
+        );
+    }
+}
@@ -0,0 +1,493 @@
+use std::ops::{Add, Mul, Sub};
+
+use nannou::glam::Vec2;
+
+use crate::ShapeError;
+
+/// Maximum depth of subdivision when flattening with [Flattening::Tolerance].
+const MAX_SUBDIVISION_DEPTH: u32 = 16;
+
+/// Strategy used to turn curves into polylines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flattening {
+    /// Splits every curve into `n * degree` steps evenly distributed in `t`.
+    Divisions(usize),
+    /// Subdivides curves until the polyline is never further away from
+    /// the curve than the given distance (in pixels).
+    Tolerance(f32),
+}
+
+impl Default for Flattening {
+    fn default() -> Self {
+        Flattening::Divisions(10)
+    }
+}
+
+/// Bézier curve of an arbitrary degree, evaluated with De Casteljau's algorithm.
+///
+/// Unlike the Bernstein polynomial form it never computes binomial coefficients,
+/// so it stays numerically stable for curves with many control points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BezierCurve<Point> {
+    points: Vec<Point>,
+}
+
+impl<Point> BezierCurve<Point>
+where
+    Point: Mul<f32, Output = Point> + Add<Point, Output = Point> + Copy,
+{
+    /// Creates a curve from its control points (including both end points).
+    pub fn new(points: Vec<Point>) -> Result<Self, ShapeError> {
+        if points.is_empty() {
+            return Err(ShapeError::NoPoints);
+        }
+        Ok(BezierCurve { points })
+    }
+
+    /// Curve made of points known not to be empty.
+    pub(crate) fn from_points(points: Vec<Point>) -> Self {
+        debug_assert!(!points.is_empty());
+        BezierCurve { points }
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn degree(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    pub fn start(&self) -> Point {
+        self.points[0]
+    }
+
+    pub fn end(&self) -> Point {
+        self.points[self.points.len() - 1]
+    }
+
+    /// Point on the curve for parameter `t` in `[0, 1]`.
+    pub fn point_at(&self, t: f32) -> Point {
+        let mut work = self.points.clone();
+        de_casteljau(&mut work, t);
+        work[0]
+    }
+
+    /// Splits the curve at `t` into two curves of the same degree.
+    ///
+    /// The first one covers `[0, t]` and the second one `[t, 1]` of the original curve.
+    pub fn split_at(&self, t: f32) -> (Self, Self) {
+        let n = self.points.len();
+        let mut work = self.points.clone();
+        let mut left = Vec::with_capacity(n);
+        let mut right = Vec::with_capacity(n);
+        left.push(work[0]);
+        right.push(work[n - 1]);
+        for level in 1..n {
+            for i in 0..(n - level) {
+                work[i] = lerp(work[i], work[i + 1], t);
+            }
+            left.push(work[0]);
+            right.push(work[n - level - 1]);
+        }
+        right.reverse();
+        (BezierCurve { points: left }, BezierCurve { points: right })
+    }
+
+    /// Flattens the curve into `divisions + 1` points evenly distributed in `t`.
+    pub fn flatten(&self, divisions: usize) -> Vec<Point> {
+        let divisions = divisions.max(1);
+        let mut work = Vec::with_capacity(self.points.len());
+        (0..=divisions)
+            .map(|i| {
+                work.clear();
+                work.extend_from_slice(&self.points);
+                de_casteljau(&mut work, i as f32 / divisions as f32);
+                work[0]
+            })
+            .collect()
+    }
+}
+
+impl<Point> BezierCurve<Point>
+where
+    Point:
+        Mul<f32, Output = Point> + Add<Point, Output = Point> + Sub<Point, Output = Point> + Copy,
+{
+    /// First derivative of the curve at `t` (not normalized).
+    ///
+    /// For a degree 0 curve the zero vector is returned.
+    pub fn tangent_at(&self, t: f32) -> Point {
+        let n = self.degree();
+        if n == 0 {
+            return self.points[0] * 0.0;
+        }
+        let mut work = self
+            .points
+            .windows(2)
+            .map(|w| (w[1] - w[0]) * n as f32)
+            .collect::<Vec<_>>();
+        de_casteljau(&mut work, t);
+        work[0]
+    }
+}
+
+impl BezierCurve<Vec2> {
+    /// Flattens the curve into a polyline using the given strategy.
+    pub fn flatten_with(&self, flattening: Flattening) -> Vec<Vec2> {
+        match flattening {
+            Flattening::Divisions(n) => self.flatten(n * self.degree()),
+            Flattening::Tolerance(tolerance) => self
+                .flatten_parametrized(tolerance)
+                .into_iter()
+                .map(|(_, point)| point)
+                .collect(),
+        }
+    }
+
+    /// Flattens the curve to `tolerance` like [BezierCurve::flatten_with],
+    /// pairing every point with its parameter `t`.
+    pub(crate) fn flatten_parametrized(&self, tolerance: f32) -> Vec<(f32, Vec2)> {
+        let mut points = vec![(0.0, self.start())];
+        flatten_to_tolerance(
+            self,
+            tolerance,
+            MAX_SUBDIVISION_DEPTH,
+            (0.0, 1.0),
+            &mut points,
+        );
+        points
+    }
+
+    /// Largest distance of a control point from the chord between the end points.
+    ///
+    /// The curve lies in the convex hull of its control points, so it is never
+    /// further away from the chord than this value.
+    pub fn flatness(&self) -> f32 {
+        let (start, end) = (self.start(), self.end());
+        self.points
+            .iter()
+            .map(|p| distance_to_segment(*p, start, end))
+            .fold(0.0, f32::max)
+    }
+}
+
+/// `range` is the interval of the original curve's parameter covered by `curve`.
+fn flatten_to_tolerance(
+    curve: &BezierCurve<Vec2>,
+    tolerance: f32,
+    depth: u32,
+    range: (f32, f32),
+    output: &mut Vec<(f32, Vec2)>,
+) {
+    if depth == 0 || curve.flatness() <= tolerance {
+        output.push((range.1, curve.end()));
+    } else {
+        let (left, right) = curve.split_at(0.5);
+        let middle = (range.0 + range.1) * 0.5;
+        flatten_to_tolerance(&left, tolerance, depth - 1, (range.0, middle), output);
+        flatten_to_tolerance(&right, tolerance, depth - 1, (middle, range.1), output);
+    }
+}
+
+pub(crate) fn distance_to_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared == 0.0 {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+/// Runs De Casteljau's reduction in place, leaving the result in `points[0]`.
+fn de_casteljau<Point>(points: &mut [Point], t: f32)
+where
+    Point: Mul<f32, Output = Point> + Add<Point, Output = Point> + Copy,
+{
+    let n = points.len();
+    for level in 1..n {
+        for i in 0..(n - level) {
+            points[i] = lerp(points[i], points[i + 1], t);
+        }
+    }
+}
+
+fn lerp<Point>(a: Point, b: Point, t: f32) -> Point
+where
+    Point: Mul<f32, Output = Point> + Add<Point, Output = Point> + Copy,
+{
+    a * (1.0 - t) + b * t
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::glam::{vec2, Vec2};
+    use proptest::prelude::*;
+
+    use super::{distance_to_segment, BezierCurve, Flattening};
+    use crate::ShapeError;
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-3, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(BezierCurve::<Vec2>::new(vec![]), Err(ShapeError::NoPoints));
+        assert_eq!(
+            BezierCurve::new(vec![vec2(1.0, 2.0)]).map(|c| c.degree()),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_end_points() {
+        let curve =
+            BezierCurve::from_points(vec![vec2(0.0, 0.0), vec2(50.0, 50.0), vec2(100.0, 0.0)]);
+        assert_eq!(curve.point_at(0.0), vec2(0.0, 0.0));
+        assert_eq!(curve.point_at(1.0), vec2(100.0, 0.0));
+        assert_close(curve.point_at(0.5), vec2(50.0, 25.0));
+    }
+
+    #[test]
+    fn test_high_degree_curve_is_stable() {
+        // evenly spaced collinear control points describe the line `x = 60 * t`
+        let curve = BezierCurve::from_points((0..=60).map(|i| vec2(i as f32, 0.0)).collect());
+        assert_eq!(curve.degree(), 60);
+        for i in 0..=10 {
+            let t = i as f32 * 0.1;
+            assert_close(curve.point_at(t), vec2(60.0 * t, 0.0));
+            assert_close(curve.tangent_at(t), vec2(60.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_tangent() {
+        let curve =
+            BezierCurve::from_points(vec![vec2(0.0, 0.0), vec2(50.0, 50.0), vec2(100.0, 0.0)]);
+        assert_close(curve.tangent_at(0.0), vec2(100.0, 100.0));
+        assert_close(curve.tangent_at(0.5), vec2(100.0, 0.0));
+        assert_close(curve.tangent_at(1.0), vec2(100.0, -100.0));
+        assert_eq!(
+            BezierCurve::from_points(vec![vec2(3.0, 4.0)]).tangent_at(0.5),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let curve = BezierCurve::from_points(vec![
+            vec2(0.0, 0.0),
+            vec2(10.0, 40.0),
+            vec2(60.0, -20.0),
+            vec2(100.0, 10.0),
+        ]);
+        let (left, right) = curve.split_at(0.3);
+        assert_eq!(left.degree(), 3);
+        assert_eq!(right.degree(), 3);
+        assert_eq!(left.start(), curve.start());
+        assert_eq!(right.end(), curve.end());
+        assert_close(left.end(), curve.point_at(0.3));
+        assert_close(right.start(), curve.point_at(0.3));
+        for i in 0..=10 {
+            let t = i as f32 * 0.1;
+            assert_close(left.point_at(t), curve.point_at(0.3 * t));
+            assert_close(right.point_at(t), curve.point_at(0.3 + 0.7 * t));
+        }
+    }
+
+    fn max_deviation(curve: &BezierCurve<Vec2>, polyline: &[Vec2]) -> f32 {
+        (0..=1000)
+            .map(|i| curve.point_at(i as f32 / 1000.0))
+            .map(|p| {
+                polyline
+                    .windows(2)
+                    .map(|w| distance_to_segment(p, w[0], w[1]))
+                    .fold(f32::MAX, f32::min)
+            })
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_flatten_divisions() {
+        let curve =
+            BezierCurve::from_points(vec![vec2(0.0, 0.0), vec2(50.0, 50.0), vec2(100.0, 0.0)]);
+        assert_eq!(curve.flatten_with(Flattening::default()).len(), 21);
+        assert_eq!(curve.flatten_with(Flattening::Divisions(3)).len(), 7);
+    }
+
+    #[test]
+    fn test_flatten_tolerance_stays_close_to_curve() {
+        let curve = BezierCurve::from_points(vec![
+            vec2(0.0, 0.0),
+            vec2(-40.0, 300.0),
+            vec2(250.0, -120.0),
+            vec2(400.0, 80.0),
+            vec2(310.0, 260.0),
+        ]);
+        for tolerance in [5.0, 1.0, 0.25, 0.05] {
+            let points = curve.flatten_with(Flattening::Tolerance(tolerance));
+            assert_eq!(points[0], curve.start());
+            assert_eq!(points[points.len() - 1], curve.end());
+            assert!(max_deviation(&curve, &points) <= tolerance);
+        }
+    }
+
+    #[test]
+    fn test_flatten_tolerance_adapts_to_size() {
+        let small = BezierCurve::from_points(vec![vec2(0.0, 0.0), vec2(2.0, 4.0), vec2(4.0, 0.0)]);
+        let large =
+            BezierCurve::from_points(vec![vec2(0.0, 0.0), vec2(200.0, 400.0), vec2(400.0, 0.0)]);
+        let tolerance = Flattening::Tolerance(0.5);
+        let divisions = Flattening::default();
+        assert!(small.flatten_with(tolerance).len() < small.flatten_with(divisions).len());
+        assert!(large.flatten_with(tolerance).len() > large.flatten_with(divisions).len());
+    }
+
+    #[test]
+    fn test_flatten_straight_line() {
+        let line =
+            BezierCurve::from_points(vec![vec2(0.0, 0.0), vec2(50.0, 0.0), vec2(100.0, 0.0)]);
+        assert_eq!(
+            line.flatten_with(Flattening::Tolerance(0.1)),
+            vec![vec2(0.0, 0.0), vec2(100.0, 0.0)]
+        );
+    }
+
+    fn bezier_curve(points: Vec<Vec2>) -> Vec<Vec2> {
+        BezierCurve::from_points(points).flatten_with(Flattening::default())
+    }
+
+    fn assert_points_eq(points: Vec<Vec2>, expected: Vec<Vec2>) {
+        assert_eq!(points.len(), expected.len());
+        for (p, e) in points.iter().zip(expected.iter()) {
+            assert!(p.distance(*e) < 1e-3, "{:?} != {:?}", points, expected);
+        }
+    }
+
+    #[test]
+    fn test_bezier_curve() {
+        let points = bezier_curve(vec![vec2(0.0, 0.0), vec2(0.0, 10.0)]);
+        assert_points_eq(points, (0..=10).map(|i| vec2(0.0, i as f32)).collect());
+    }
+    #[test]
+    fn test_bezier_curve1() {
+        let points = bezier_curve(vec![vec2(0.0, 0.0), vec2(150.0, 100.0)]);
+        assert_points_eq(
+            points,
+            (0..=10).map(|i| vec2(15.0, 10.0) * i as f32).collect(),
+        );
+    }
+
+    #[test]
+    fn test_bezier_curve_quadratic() {
+        let points = bezier_curve(vec![vec2(0.0, 0.0), vec2(50.0, 50.0), vec2(100.0, 0.0)]);
+        assert_points_eq(
+            points,
+            (0..=20)
+                .map(|i| i as f32 / 20.0)
+                .map(|t| vec2(100.0 * t, 100.0 * t * (1.0 - t)))
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn test_bezier_curve_many_points() {
+        let points = bezier_curve((0..=40).map(|i| vec2(i as f32, 0.0)).collect());
+        assert_eq!(points.len(), 401);
+        assert_eq!(points[0], vec2(0.0, 0.0));
+        assert_eq!(points[400], vec2(40.0, 0.0));
+    }
+
+    /// Rounding errors allowed by the properties, relative to coordinates up to 1000.
+    const PROPERTY_EPSILON: f32 = 0.05;
+
+    fn point() -> impl Strategy<Value = Vec2> {
+        (-1000.0f32..1000.0, -1000.0f32..1000.0).prop_map(|(x, y)| vec2(x, y))
+    }
+
+    fn curve() -> impl Strategy<Value = BezierCurve<Vec2>> {
+        prop::collection::vec(point(), 2..=8).prop_map(BezierCurve::from_points)
+    }
+
+    /// Convex hull of `points`, counter-clockwise (Andrew's monotone chain).
+    fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        sorted.dedup();
+        if sorted.len() < 3 {
+            return sorted;
+        }
+        let half = |points: &mut dyn Iterator<Item = Vec2>| {
+            let mut half: Vec<Vec2> = vec![];
+            for p in points {
+                while half.len() >= 2
+                    && (half[half.len() - 1] - half[half.len() - 2])
+                        .perp_dot(p - half[half.len() - 2])
+                        <= 0.0
+                {
+                    half.pop();
+                }
+                half.push(p);
+            }
+            half.pop();
+            half
+        };
+        let mut hull = half(&mut sorted.iter().copied());
+        hull.extend(half(&mut sorted.iter().rev().copied()));
+        hull
+    }
+
+    fn hull_contains(hull: &[Vec2], point: Vec2) -> bool {
+        let edges = || (0..hull.len()).map(|i| (hull[i], hull[(i + 1) % hull.len()]));
+        let inside = hull.len() >= 3 && edges().all(|(a, b)| (b - a).perp_dot(point - a) >= 0.0);
+        inside
+            || edges()
+                .map(|(a, b)| distance_to_segment(point, a, b))
+                .fold(f32::INFINITY, f32::min)
+                < PROPERTY_EPSILON
+    }
+
+    proptest! {
+        #[test]
+        fn prop_end_points_are_interpolated(curve in curve(), tolerance in 0.25f32..10.0) {
+            prop_assert_eq!(curve.point_at(0.0), curve.start());
+            prop_assert_eq!(curve.point_at(1.0), curve.end());
+            for flattening in [Flattening::Divisions(3), Flattening::Tolerance(tolerance)] {
+                let points = curve.flatten_with(flattening);
+                prop_assert_eq!(points[0], curve.start());
+                prop_assert_eq!(points[points.len() - 1], curve.end());
+            }
+        }
+
+        #[test]
+        fn prop_curve_stays_within_control_hull(curve in curve(), t in 0.0f32..=1.0) {
+            let hull = convex_hull(curve.points());
+            let point = curve.point_at(t);
+            prop_assert!(hull_contains(&hull, point), "{:?} outside of {:?}", point, hull);
+        }
+
+        #[test]
+        fn prop_split_halves_rejoin(curve in curve(), at in 0.01f32..0.99, t in 0.0f32..=1.0) {
+            let (left, right) = curve.split_at(at);
+            prop_assert_eq!(left.start(), curve.start());
+            prop_assert_eq!(left.end(), right.start());
+            prop_assert_eq!(right.end(), curve.end());
+            // following the left then the right half retraces the whole curve
+            let rejoined = if t <= at {
+                left.point_at(t / at)
+            } else {
+                right.point_at((t - at) / (1.0 - at))
+            };
+            let expected = curve.point_at(t);
+            prop_assert!(rejoined.distance(expected) < PROPERTY_EPSILON, "{:?} != {:?}", rejoined, expected);
+        }
+
+        #[test]
+        fn prop_flattening_stays_within_tolerance(curve in curve(), tolerance in 0.25f32..10.0) {
+            let points = curve.flatten_with(Flattening::Tolerance(tolerance));
+            prop_assert!(max_deviation(&curve, &points) <= tolerance + PROPERTY_EPSILON);
+        }
+    }
+}
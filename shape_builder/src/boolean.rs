@@ -0,0 +1,385 @@
+use std::collections::{HashMap, HashSet};
+
+use nannou::glam::Vec2;
+
+use crate::polygon::{rings, winding_number};
+use crate::{Flattening, Path, Subpath};
+
+/// Distance under which points are considered to be the same.
+const EPSILON: f32 = 1e-4;
+/// Distance from an edge at which its sides are probed.
+const SIDE_OFFSET: f32 = 1e-3;
+
+impl Path {
+    /// Outline of the area covered by this path or `other`.
+    ///
+    /// Like every boolean operation, the inside of a path follows the non-zero
+    /// rule and the result is made of closed polylines, counter-clockwise
+    /// around filled areas and clockwise around holes.
+    pub fn union(&self, other: &Path, flattening: Flattening) -> Path {
+        boolean(&[self, other], flattening, |inside| inside[0] || inside[1])
+    }
+
+    /// Outline of the area covered by both this path and `other`.
+    pub fn intersection(&self, other: &Path, flattening: Flattening) -> Path {
+        boolean(&[self, other], flattening, |inside| inside[0] && inside[1])
+    }
+
+    /// Outline of the area covered by this path but not by `other`.
+    pub fn difference(&self, other: &Path, flattening: Flattening) -> Path {
+        boolean(&[self, other], flattening, |inside| inside[0] && !inside[1])
+    }
+
+    /// Outline of the area covered by any of the `paths`.
+    ///
+    /// Also resolves self overlapping paths when given a single one.
+    pub fn union_all<'p>(
+        paths: impl IntoIterator<Item = &'p Path>,
+        flattening: Flattening,
+    ) -> Path {
+        let paths = paths.into_iter().collect::<Vec<_>>();
+        boolean(&paths, flattening, |inside| inside.iter().any(|i| *i))
+    }
+}
+
+/// Builds the outline of the area where `keep` holds, given whether
+/// a point is inside each of the `paths`.
+fn boolean(paths: &[&Path], flattening: Flattening, keep: impl Fn(&[bool]) -> bool) -> Path {
+    let operands = paths
+        .iter()
+        .map(|path| rings(path, flattening))
+        .collect::<Vec<_>>();
+    let mut inside = vec![false; operands.len()];
+    resolve(operands.iter().flatten(), |point| {
+        for (inside, rings) in inside.iter_mut().zip(&operands) {
+            *inside = winding_number(rings, point) != 0;
+        }
+        keep(&inside)
+    })
+}
+
+/// Outline of the area where `is_inside` holds, built from the edges of
+/// the polygons `rings` that separate inside from outside.
+pub(crate) fn resolve<'r>(
+    rings: impl IntoIterator<Item = &'r Vec<Vec2>>,
+    mut is_inside: impl FnMut(Vec2) -> bool,
+) -> Path {
+    let edges = rings
+        .into_iter()
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+        .collect::<Vec<_>>();
+
+    // boundary edges of the result, oriented to have its inside on their left
+    let mut boundary = vec![];
+    let mut seen = HashSet::new();
+    for (from, to) in split_edges(&edges) {
+        let middle = (from + to) * 0.5;
+        let normal = (to - from).normalize().perp() * SIDE_OFFSET;
+        let edge = match (is_inside(middle + normal), is_inside(middle - normal)) {
+            (true, false) => (from, to),
+            (false, true) => (to, from),
+            _ => continue,
+        };
+        // overlapping edges of several polygons are only needed once
+        if seen.insert((key(edge.0), key(edge.1))) {
+            boundary.push(edge);
+        }
+    }
+
+    chain(&boundary)
+        .into_iter()
+        .map(|mut ring| {
+            // end with a line back to the start, like ShapeBuilder::close does
+            ring.push(ring[0]);
+            Subpath::from_polyline(&ring, true)
+        })
+        .collect()
+}
+
+/// Splits the edges at all points where they cross or touch each other.
+fn split_edges(edges: &[(Vec2, Vec2)]) -> Vec<(Vec2, Vec2)> {
+    let mut splits = vec![vec![]; edges.len()];
+    for i in 0..edges.len() {
+        for j in (i + 1)..edges.len() {
+            intersect(edges[i], edges[j], &mut |t, u, point| {
+                if let Some(t) = t {
+                    splits[i].push((t, point));
+                }
+                if let Some(u) = u {
+                    splits[j].push((u, point));
+                }
+            });
+        }
+    }
+
+    let mut split = vec![];
+    for (&(from, to), points) in edges.iter().zip(&mut splits) {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut start = from;
+        for &(_, point) in points.iter().chain([&(1.0, to)]) {
+            if point != start {
+                split.push((start, point));
+                start = point;
+            }
+        }
+    }
+    split
+}
+
+/// Finds where two edges meet and reports the position along each edge
+/// that has to be split there (`None` when the point is one of its ends).
+fn intersect(
+    (a0, a1): (Vec2, Vec2),
+    (b0, b1): (Vec2, Vec2),
+    split: &mut impl FnMut(Option<f32>, Option<f32>, Vec2),
+) {
+    let min = a0.min(a1).max(b0.min(b1));
+    let max = a0.max(a1).min(b0.max(b1));
+    if min.x > max.x + EPSILON || min.y > max.y + EPSILON {
+        return;
+    }
+    let (r, s) = (a1 - a0, b1 - b0);
+    let (r_length, s_length) = (r.length(), s.length());
+    if r_length <= EPSILON || s_length <= EPSILON {
+        return;
+    }
+    // position along an edge, `None` when near or beyond its ends
+    let inner =
+        |t: f32, length: f32| (t * length > EPSILON && (1.0 - t) * length > EPSILON).then_some(t);
+
+    let denominator = r.perp_dot(s);
+    let offset = b0 - a0;
+    if denominator.abs() <= EPSILON * r_length * s_length {
+        if offset.perp_dot(r).abs() > EPSILON * r_length {
+            // parallel
+            return;
+        }
+        // collinear edges are split at the ends of the other edge
+        for point in [b0, b1] {
+            if let Some(t) = inner((point - a0).dot(r) / (r_length * r_length), r_length) {
+                split(Some(t), None, point);
+            }
+        }
+        for point in [a0, a1] {
+            if let Some(u) = inner((point - b0).dot(s) / (s_length * s_length), s_length) {
+                split(None, Some(u), point);
+            }
+        }
+        return;
+    }
+
+    let t = offset.perp_dot(s) / denominator;
+    let u = offset.perp_dot(r) / denominator;
+    let outside = |t: f32, length: f32| t * length < -EPSILON || (t - 1.0) * length > EPSILON;
+    if outside(t, r_length) || outside(u, s_length) {
+        return;
+    }
+    let (t_inner, u_inner) = (inner(t, r_length), inner(u, s_length));
+    // reuse the exact end point when an edge touches the other one
+    let point = match (t_inner, u_inner) {
+        (None, _) if t < 0.5 => a0,
+        (None, _) => a1,
+        (_, None) if u < 0.5 => b0,
+        (_, None) => b1,
+        _ => a0 + r * t,
+    };
+    if t_inner.is_some() || u_inner.is_some() {
+        split(t_inner, u_inner, point);
+    }
+}
+
+/// Connects oriented edges into closed polygons.
+fn chain(edges: &[(Vec2, Vec2)]) -> Vec<Vec<Vec2>> {
+    let mut outgoing = HashMap::<_, Vec<usize>>::new();
+    for (i, (from, _)) in edges.iter().enumerate() {
+        outgoing.entry(key(*from)).or_default().push(i);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut rings = vec![];
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        let mut ring = vec![edges[first].0];
+        let mut current = first;
+        loop {
+            used[current] = true;
+            let end = edges[current].1;
+            if end == ring[0] {
+                break;
+            }
+            ring.push(end);
+            let next = outgoing
+                .get(&key(end))
+                .and_then(|candidates| candidates.iter().find(|&&i| !used[i]));
+            match next {
+                Some(&next) => current = next,
+                None => break,
+            }
+        }
+        let ring = simplify(ring);
+        if ring.len() >= 3 {
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+/// Removes points lying on the straight line between their neighbours.
+fn simplify(ring: Vec<Vec2>) -> Vec<Vec2> {
+    let mut simplified: Vec<Vec2> = Vec::with_capacity(ring.len());
+    for point in ring.iter().chain(ring.first()) {
+        while simplified.len() >= 2 {
+            let [previous, middle] = [
+                simplified[simplified.len() - 2],
+                simplified[simplified.len() - 1],
+            ];
+            if !is_straight(previous, middle, *point) {
+                break;
+            }
+            simplified.pop();
+        }
+        simplified.push(*point);
+    }
+    simplified.pop();
+    // the start point itself may lie on the line closing the polygon
+    if simplified.len() >= 3
+        && is_straight(
+            simplified[simplified.len() - 1],
+            simplified[0],
+            simplified[1],
+        )
+    {
+        simplified.remove(0);
+    }
+    simplified
+}
+
+fn is_straight(previous: Vec2, middle: Vec2, next: Vec2) -> bool {
+    let chord = next - previous;
+    let length = chord.length();
+    length > EPSILON
+        && (middle - previous).perp_dot(chord).abs() <= EPSILON * length
+        && (middle - previous).dot(next - middle) >= 0.0
+}
+
+fn key(point: Vec2) -> (u32, u32) {
+    // adding zero turns -0 into 0
+    let point = point + Vec2::ZERO;
+    (point.x.to_bits(), point.y.to_bits())
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::glam::{vec2, Vec2};
+
+    use crate::polygon::signed_area;
+    use crate::{Flattening, Path, PathBuilder, ShapeBuilder, ShapeError};
+
+    fn polygon(points: &[Vec2]) -> Result<Path, ShapeError> {
+        Ok(points[1..]
+            .iter()
+            .try_fold(PathBuilder::new(points[0])?, |b, p| b.line_to(*p))?
+            .close()
+            .build())
+    }
+
+    fn square(min: Vec2, size: f32) -> Result<Path, ShapeError> {
+        polygon(&[
+            min,
+            min + vec2(size, 0.0),
+            min + vec2(size, size),
+            min + vec2(0.0, size),
+        ])
+    }
+
+    /// Signed area and number of corners of every subpath.
+    fn rings(path: &Path) -> Vec<(f32, usize)> {
+        path.flatten(Flattening::default())
+            .iter()
+            .map(|ring| {
+                assert_eq!(ring.first(), ring.last());
+                (signed_area(&ring[1..]), ring.len() - 1)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_overlapping_squares() -> Result<(), ShapeError> {
+        let a = square(vec2(0.0, 0.0), 10.0)?;
+        let b = square(vec2(5.0, 5.0), 10.0)?;
+        let flattening = Flattening::default();
+        assert_eq!(rings(&a.union(&b, flattening)), vec![(175.0, 8)]);
+        assert_eq!(rings(&a.intersection(&b, flattening)), vec![(25.0, 4)]);
+        assert_eq!(rings(&a.difference(&b, flattening)), vec![(75.0, 6)]);
+        assert_eq!(rings(&b.difference(&a, flattening)), vec![(75.0, 6)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_orientation_does_not_matter() -> Result<(), ShapeError> {
+        let a = square(vec2(0.0, 0.0), 10.0)?;
+        let clockwise = polygon(&[
+            vec2(5.0, 5.0),
+            vec2(5.0, 15.0),
+            vec2(15.0, 15.0),
+            vec2(15.0, 5.0),
+        ])?;
+        assert_eq!(
+            rings(&a.union(&clockwise, Flattening::default())),
+            vec![(175.0, 8)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_disjoint_squares() -> Result<(), ShapeError> {
+        let a = square(vec2(0.0, 0.0), 10.0)?;
+        let b = square(vec2(20.0, 0.0), 10.0)?;
+        let flattening = Flattening::default();
+        assert_eq!(
+            rings(&a.union(&b, flattening)),
+            vec![(100.0, 4), (100.0, 4)]
+        );
+        assert!(a.intersection(&b, flattening).is_empty());
+        assert_eq!(rings(&a.difference(&b, flattening)), vec![(100.0, 4)]);
+        assert_eq!(rings(&a.union(&a, flattening)), vec![(100.0, 4)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hole() -> Result<(), ShapeError> {
+        let outer = square(vec2(0.0, 0.0), 10.0)?;
+        let inner = square(vec2(4.0, 4.0), 2.0)?;
+        let mut result = rings(&outer.difference(&inner, Flattening::default()));
+        result.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(result, vec![(-4.0, 4), (100.0, 4)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_edges() -> Result<(), ShapeError> {
+        let a = square(vec2(0.0, 0.0), 10.0)?;
+        let b = square(vec2(10.0, 0.0), 10.0)?;
+        let c = square(vec2(0.0, 5.0), 10.0)?;
+        let union = Path::union_all([&a, &b, &c], Flattening::default());
+        assert_eq!(rings(&union), vec![(250.0, 6)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_overlapping_path() -> Result<(), ShapeError> {
+        // two triangles sharing the diagonal of a square, like the halves of a petal
+        let lobes = polygon(&[
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 10.0),
+        ])?;
+        let union = Path::union_all([&lobes], Flattening::default());
+        assert_eq!(rings(&union), vec![(100.0, 4)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use std::f32::consts::PI;
+
+use nannou::glam::{vec2, Vec2};
+use nannou::noise::{NoiseFn, Perlin};
+
+use crate::GenerateEdge;
+
+/// Edge displaced sideways by Perlin noise.
+///
+/// The displacement fades out towards both ends, so the edge always
+/// connects the given points.
+#[derive(Debug, Clone)]
+pub struct NoisyEdge {
+    pub noise: Perlin,
+    /// Largest displacement (in pixels).
+    pub amplitude: f32,
+    /// Noise frequency along the edge (per pixel).
+    pub frequency: f32,
+    /// Distance between generated points (in pixels).
+    pub step: f32,
+    /// Second noise coordinate, makes edges sharing one [Perlin] differ.
+    pub offset: f64,
+}
+
+impl NoisyEdge {
+    pub fn new(noise: Perlin, amplitude: f32) -> Self {
+        NoisyEdge {
+            noise,
+            amplitude,
+            frequency: 0.05,
+            step: 4.0,
+            offset: 0.0,
+        }
+    }
+}
+
+impl GenerateEdge<Vec2> for NoisyEdge {
+    fn generate_edge(&self, start: Vec2, end: Vec2) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        let length = start.distance(end);
+        let normal = left_normal(start, end);
+        let steps = (length / self.step.max(f32::EPSILON)).ceil().max(1.0) as usize;
+        Box::new((1..=steps).map(move |i| {
+            if i == steps {
+                return end;
+            }
+            let t = i as f32 / steps as f32;
+            let noise = self
+                .noise
+                .get([(t * length * self.frequency) as f64, self.offset])
+                as f32;
+            let envelope = (t * PI).sin();
+            start.lerp(end, t) + normal * (noise * envelope * self.amplitude)
+        }))
+    }
+}
+
+/// Edge made of triangular teeth, like a serrated leaf.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZigZagEdge {
+    pub teeth: usize,
+    /// Height of a tooth, positive values point to the left of the edge direction.
+    pub depth: f32,
+    /// Position of the tooth tip within the tooth, `0.5` makes symmetric teeth
+    /// and values closer to `1.0` make them lean towards the end of the edge.
+    pub skew: f32,
+}
+
+impl ZigZagEdge {
+    pub fn new(teeth: usize, depth: f32) -> Self {
+        ZigZagEdge {
+            teeth,
+            depth,
+            skew: 0.5,
+        }
+    }
+}
+
+impl GenerateEdge<Vec2> for ZigZagEdge {
+    fn generate_edge(&self, start: Vec2, end: Vec2) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        let teeth = self.teeth.max(1);
+        let normal = left_normal(start, end);
+        let skew = self.skew.clamp(0.0, 1.0);
+        Box::new((0..teeth).flat_map(move |i| {
+            let tip = (i as f32 + skew) / teeth as f32;
+            let base = match i + 1 {
+                last if last == teeth => end,
+                next => start.lerp(end, next as f32 / teeth as f32),
+            };
+            [start.lerp(end, tip) + normal * self.depth, base]
+        }))
+    }
+}
+
+/// Edge made of rounded bumps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScallopedEdge {
+    pub count: usize,
+    /// Height of a bump, positive values point to the left of the edge direction.
+    pub depth: f32,
+    /// Number of points generated for every bump.
+    pub steps: usize,
+}
+
+impl ScallopedEdge {
+    pub fn new(count: usize, depth: f32) -> Self {
+        ScallopedEdge {
+            count,
+            depth,
+            steps: 8,
+        }
+    }
+}
+
+impl GenerateEdge<Vec2> for ScallopedEdge {
+    fn generate_edge(&self, start: Vec2, end: Vec2) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        let count = self.count.max(1);
+        let steps = self.steps.max(1);
+        let normal = left_normal(start, end);
+        let total = count * steps;
+        Box::new((1..=total).map(move |i| {
+            if i == total {
+                return end;
+            }
+            let t = i as f32 / total as f32;
+            let bump = ((i % steps) as f32 / steps as f32 * PI).sin();
+            start.lerp(end, t) + normal * (bump * self.depth)
+        }))
+    }
+}
+
+/// Unit vector perpendicular to the edge, pointing to its left side.
+fn left_normal(start: Vec2, end: Vec2) -> Vec2 {
+    let direction = (end - start).normalize_or_zero();
+    vec2(-direction.y, direction.x)
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::glam::{vec2, Vec2};
+    use nannou::noise::Perlin;
+
+    use super::{NoisyEdge, ScallopedEdge, ZigZagEdge};
+    use crate::{GenerateEdge, PathBuilder, ShapeBuilder, ShapeError};
+
+    fn edge(generator: &dyn GenerateEdge<Vec2>) -> Vec<Vec2> {
+        generator
+            .generate_edge(vec2(0.0, 0.0), vec2(100.0, 0.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_zig_zag() {
+        let points = edge(&ZigZagEdge::new(4, 5.0));
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[0], vec2(12.5, 5.0));
+        assert_eq!(points[1], vec2(25.0, 0.0));
+        assert_eq!(points[7], vec2(100.0, 0.0));
+
+        let serrated = ZigZagEdge {
+            skew: 0.8,
+            ..ZigZagEdge::new(2, -5.0)
+        };
+        assert_eq!(edge(&serrated)[0], vec2(40.0, -5.0));
+    }
+
+    #[test]
+    fn test_scalloped() {
+        let points = edge(&ScallopedEdge::new(5, 10.0));
+        assert_eq!(points.len(), 40);
+        assert_eq!(points[3], vec2(10.0, 10.0));
+        assert_eq!(points[7], vec2(20.0, 0.0));
+        assert_eq!(points[39], vec2(100.0, 0.0));
+        assert!(points.iter().all(|p| p.y >= 0.0 && p.y <= 10.0));
+    }
+
+    #[test]
+    fn test_noisy() {
+        let mut generator = NoisyEdge::new(Perlin::new(), 8.0);
+        generator.offset = 0.3;
+        let points = edge(&generator);
+        assert_eq!(points.len(), 25);
+        assert_eq!(points[24], vec2(100.0, 0.0));
+        assert!(points.iter().all(|p| p.y.abs() <= 8.0));
+        assert!(points.iter().any(|p| p.y.abs() > 0.1));
+    }
+
+    #[test]
+    fn test_exact_end() {
+        // lerp rounding would miss this end by a few ulps
+        let (start, end) = (vec2(0.7, -2.9), vec2(0.1, 0.7));
+        let generators: [&dyn GenerateEdge<Vec2>; 3] = [
+            &ZigZagEdge::new(7, 3.0),
+            &ScallopedEdge::new(3, 2.0),
+            &NoisyEdge::new(Perlin::new(), 4.0),
+        ];
+        for generator in generators {
+            assert_eq!(generator.generate_edge(start, end).last(), Some(end));
+        }
+    }
+
+    #[test]
+    fn test_add_edge() -> Result<(), ShapeError> {
+        let path = PathBuilder::new(vec2(0.0, 0.0))?
+            .add_edge(&ZigZagEdge::new(2, 5.0), vec2(0.0, 100.0))?
+            .close()
+            .build();
+        let subpath = &path.subpaths()[0];
+        // four zig-zag lines and the closing one
+        assert_eq!(subpath.segments().len(), 5);
+        assert_eq!(subpath.segments()[0].end(), vec2(-5.0, 25.0));
+        assert_eq!(subpath.segments()[3].end(), vec2(0.0, 100.0));
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::{self, Display};
+
+use nannou::glam::Vec2;
+
+use crate::Flattening;
+
+/// Reasons why a [ShapeBuilder](crate::ShapeBuilder) refused to add to a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeError {
+    /// Curve, spline or edge without any point.
+    NoPoints,
+    /// Point or parameter which is NaN or infinite.
+    NotFinite,
+    /// Curve whose points are all on the current point, it would have no length.
+    ZeroLength,
+    /// Flattening without any division or with a tolerance that is not a positive number.
+    InvalidFlattening(Flattening),
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::NoPoints => write!(f, "no points given"),
+            ShapeError::NotFinite => write!(f, "point or parameter is not a finite number"),
+            ShapeError::ZeroLength => write!(f, "curve has no length"),
+            ShapeError::InvalidFlattening(flattening) => {
+                write!(f, "invalid flattening {:?}", flattening)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// Checks that there are points and that all of them are finite.
+pub(crate) fn check_points(points: &[Vec2]) -> Result<(), ShapeError> {
+    if points.is_empty() {
+        return Err(ShapeError::NoPoints);
+    }
+    check_finite(points)
+}
+
+pub(crate) fn check_finite(points: &[Vec2]) -> Result<(), ShapeError> {
+    if points.iter().all(|p| p.is_finite()) {
+        Ok(())
+    } else {
+        Err(ShapeError::NotFinite)
+    }
+}
+
+/// Checks that a curve from `from` through `points` goes somewhere.
+pub(crate) fn check_length(from: Vec2, points: &[Vec2]) -> Result<(), ShapeError> {
+    if points.iter().all(|&p| p == from) {
+        Err(ShapeError::ZeroLength)
+    } else {
+        Ok(())
+    }
+}
+
+impl Flattening {
+    pub(crate) fn check(self) -> Result<Self, ShapeError> {
+        let valid = match self {
+            Flattening::Divisions(n) => n > 0,
+            Flattening::Tolerance(tolerance) => tolerance.is_finite() && tolerance > 0.0,
+        };
+        if valid {
+            Ok(self)
+        } else {
+            Err(ShapeError::InvalidFlattening(self))
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use nannou::color::{IntoLinSrgba, LinSrgba};
+use nannou::glam::Vec2;
+
+/// Geometry along which the colors of a [Gradient] change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    /// Colors change along the line from `start` (offset 0) to `end` (offset 1).
+    Linear { start: Vec2, end: Vec2 },
+    /// Colors change with the distance from `center`, reaching offset 1 at `radius`.
+    Radial { center: Vec2, radius: f32 },
+}
+
+/// Color gradient made of color stops, used to fill shapes.
+///
+/// Colors are interpolated in linear RGB, points before the first stop
+/// or after the last one get the color of that stop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    stops: Vec<(f32, LinSrgba)>,
+}
+
+impl Gradient {
+    pub fn linear(start: Vec2, end: Vec2) -> Self {
+        Gradient {
+            shape: GradientShape::Linear { start, end },
+            stops: vec![],
+        }
+    }
+
+    pub fn radial(center: Vec2, radius: f32) -> Self {
+        Gradient {
+            shape: GradientShape::Radial { center, radius },
+            stops: vec![],
+        }
+    }
+
+    /// Adds a color at `offset`, stops sharing an offset make a hard edge.
+    pub fn with_stop(mut self, offset: f32, color: impl IntoLinSrgba<f32>) -> Self {
+        let index = self.stops.partition_point(|(o, _)| *o <= offset);
+        self.stops.insert(index, (offset, color.into_lin_srgba()));
+        self
+    }
+
+    pub fn stops(&self) -> &[(f32, LinSrgba)] {
+        &self.stops
+    }
+
+    /// Offset of `point` along the gradient.
+    pub fn offset_at(&self, point: Vec2) -> f32 {
+        match self.shape {
+            GradientShape::Linear { start, end } => {
+                let axis = end - start;
+                match axis.length_squared() {
+                    l if l > 0.0 => (point - start).dot(axis) / l,
+                    _ => 0.0,
+                }
+            }
+            GradientShape::Radial { center, radius } if radius > 0.0 => {
+                point.distance(center) / radius
+            }
+            GradientShape::Radial { .. } => 1.0,
+        }
+    }
+
+    /// Color of the gradient at `point`, transparent when there are no stops.
+    pub fn color_at(&self, point: Vec2) -> LinSrgba {
+        let offset = self.offset_at(point);
+        let after = self.stops.partition_point(|(o, _)| *o <= offset);
+        match (self.stops.get(after.wrapping_sub(1)), self.stops.get(after)) {
+            (Some(&(o1, c1)), Some(&(o2, c2))) => mix(c1, c2, (offset - o1) / (o2 - o1)),
+            (Some(&(_, color)), None) | (None, Some(&(_, color))) => color,
+            (None, None) => LinSrgba::new(0.0, 0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Longest distance over which the color may be interpolated linearly
+    /// without visibly missing the stops.
+    pub(crate) fn resolution(&self) -> f32 {
+        let extent = match self.shape {
+            GradientShape::Linear { start, end } => start.distance(end),
+            GradientShape::Radial { radius, .. } => radius.abs(),
+        };
+        let intervals = self.stops.len().saturating_sub(1).max(1);
+        extent / (4 * intervals) as f32
+    }
+}
+
+fn mix(from: LinSrgba, to: LinSrgba, t: f32) -> LinSrgba {
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    LinSrgba::new(
+        lerp(from.red, to.red),
+        lerp(from.green, to.green),
+        lerp(from.blue, to.blue),
+        lerp(from.alpha, to.alpha),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::color::LinSrgba;
+    use nannou::glam::vec2;
+
+    use super::Gradient;
+
+    fn black() -> LinSrgba {
+        LinSrgba::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    fn white() -> LinSrgba {
+        LinSrgba::new(1.0, 1.0, 1.0, 1.0)
+    }
+
+    #[test]
+    fn test_linear() {
+        let gradient = Gradient::linear(vec2(0.0, 0.0), vec2(10.0, 0.0))
+            .with_stop(1.0, white())
+            .with_stop(0.0, black());
+        assert_eq!(gradient.color_at(vec2(0.0, 5.0)), black());
+        assert_eq!(gradient.color_at(vec2(2.5, -3.0)).red, 0.25);
+        assert_eq!(gradient.color_at(vec2(10.0, 0.0)), white());
+        // outside the stops the gradient keeps the closest color
+        assert_eq!(gradient.color_at(vec2(-5.0, 0.0)), black());
+        assert_eq!(gradient.color_at(vec2(15.0, 0.0)), white());
+    }
+
+    #[test]
+    fn test_multiple_stops() {
+        let red = LinSrgba::new(1.0, 0.0, 0.0, 1.0);
+        let gradient = Gradient::linear(vec2(0.0, 0.0), vec2(0.0, 100.0))
+            .with_stop(0.0, black())
+            .with_stop(0.5, red)
+            .with_stop(0.5, white())
+            .with_stop(1.0, black());
+        assert_eq!(gradient.stops().len(), 4);
+        assert_eq!(
+            gradient.color_at(vec2(0.0, 25.0)),
+            LinSrgba::new(0.5, 0.0, 0.0, 1.0)
+        );
+        // hard edge at the middle
+        assert_eq!(gradient.color_at(vec2(0.0, 50.0)), white());
+        assert_eq!(
+            gradient.color_at(vec2(0.0, 75.0)),
+            LinSrgba::new(0.5, 0.5, 0.5, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_radial() {
+        let gradient = Gradient::radial(vec2(10.0, 10.0), 20.0)
+            .with_stop(0.0, white())
+            .with_stop(1.0, LinSrgba::new(1.0, 1.0, 1.0, 0.0));
+        assert_eq!(gradient.color_at(vec2(10.0, 10.0)), white());
+        assert_eq!(gradient.color_at(vec2(10.0, 20.0)).alpha, 0.5);
+        assert_eq!(gradient.color_at(vec2(30.0, 10.0)).alpha, 0.0);
+    }
+
+    #[test]
+    fn test_no_stops() {
+        let gradient = Gradient::radial(vec2(0.0, 0.0), 0.0);
+        assert_eq!(gradient.color_at(vec2(1.0, 1.0)).alpha, 0.0);
+    }
+}
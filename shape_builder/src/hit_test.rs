@@ -0,0 +1,165 @@
+use nannou::glam::Vec2;
+
+use crate::bezier::distance_to_segment;
+use crate::path::MEASURE_TOLERANCE;
+use crate::polygon::{rings, winding_number};
+use crate::{BoundingBox, FillRule, Flattening, Path, Subpath};
+
+impl BoundingBox {
+    /// Whether `point` is inside the rectangle or on its border.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Distance from `point` to the rectangle, zero when it is inside.
+    pub fn distance_to(&self, point: Vec2) -> f32 {
+        (self.min - point)
+            .max(point - self.max)
+            .max(Vec2::ZERO)
+            .length()
+    }
+}
+
+impl Subpath {
+    /// Distance from `point` to the closest point of the outline.
+    pub fn distance_to(&self, point: Vec2) -> f32 {
+        let points = self.flatten(Flattening::Tolerance(MEASURE_TOLERANCE));
+        if points.len() == 1 {
+            return point.distance(points[0]);
+        }
+        points
+            .windows(2)
+            .map(|w| distance_to_segment(point, w[0], w[1]))
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+impl Path {
+    /// Whether `point` is inside the area the path would fill with `fill_rule`.
+    ///
+    /// Open subpaths are treated as if they were closed, like fills do.
+    pub fn contains(&self, point: Vec2, fill_rule: FillRule) -> bool {
+        if !self.bounding_box().is_some_and(|b| b.contains(point)) {
+            return false;
+        }
+        let rings = rings(self, Flattening::Tolerance(MEASURE_TOLERANCE));
+        fill_rule.is_inside(winding_number(&rings, point))
+    }
+
+    /// Distance from `point` to the closest point of the outline,
+    /// infinite for an empty path.
+    ///
+    /// It is positive on both sides of the outline, use [Path::contains] to tell them apart.
+    pub fn distance_to(&self, point: Vec2) -> f32 {
+        self.subpaths()
+            .iter()
+            .map(|s| s.distance_to(point))
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::glam::{vec2, Vec2};
+
+    use crate::{BoundingBox, FillRule, Path, PathBuilder, ShapeBuilder, ShapeError};
+
+    fn square(min: Vec2, size: f32, clockwise: bool) -> Result<Path, ShapeError> {
+        let mut corners = vec![
+            min + vec2(size, 0.0),
+            min + vec2(size, size),
+            min + vec2(0.0, size),
+        ];
+        if clockwise {
+            corners.reverse();
+        }
+        Ok(corners
+            .into_iter()
+            .try_fold(PathBuilder::new(min)?, |b, p| b.line_to(p))?
+            .close()
+            .build())
+    }
+
+    /// Square with a smaller square inside, both going around the same way.
+    fn nested_squares() -> Result<Path, ShapeError> {
+        let outer = square(vec2(0.0, 0.0), 10.0, false)?;
+        let inner = square(vec2(3.0, 3.0), 4.0, false)?;
+        Ok(outer
+            .subpaths()
+            .iter()
+            .chain(inner.subpaths())
+            .cloned()
+            .collect())
+    }
+
+    #[test]
+    fn test_fill_rules() -> Result<(), ShapeError> {
+        let path = nested_squares()?;
+        let (ring, hole, outside) = (vec2(1.0, 5.0), vec2(5.0, 5.0), vec2(11.0, 5.0));
+        assert!(path.contains(ring, FillRule::NonZero));
+        assert!(path.contains(hole, FillRule::NonZero));
+        assert!(!path.contains(outside, FillRule::NonZero));
+        assert!(path.contains(ring, FillRule::EvenOdd));
+        assert!(!path.contains(hole, FillRule::EvenOdd));
+        assert!(!path.contains(outside, FillRule::EvenOdd));
+
+        // a hole going the other way is a hole for both rules
+        let outer = square(vec2(0.0, 0.0), 10.0, false)?;
+        let inner = square(vec2(3.0, 3.0), 4.0, true)?;
+        let path: Path = outer
+            .subpaths()
+            .iter()
+            .chain(inner.subpaths())
+            .cloned()
+            .collect();
+        assert!(!path.contains(hole, FillRule::NonZero));
+        assert!(!path.contains(hole, FillRule::EvenOdd));
+        Ok(())
+    }
+
+    #[test]
+    fn test_curved_and_open_paths() -> Result<(), ShapeError> {
+        // open half disc, closed by the fill from (10, 0) back to (-10, 0)
+        let half_disc = PathBuilder::new(vec2(-10.0, 0.0))?
+            .arc_to(Vec2::splat(10.0), 0.0, false, false, vec2(10.0, 0.0))?
+            .build();
+        assert!(half_disc.contains(vec2(0.0, 9.9), FillRule::NonZero));
+        assert!(half_disc.contains(vec2(7.0, 7.0), FillRule::NonZero));
+        assert!(!half_disc.contains(vec2(7.2, 7.2), FillRule::NonZero));
+        assert!(!half_disc.contains(vec2(0.0, -1.0), FillRule::NonZero));
+        assert!(!Path::default().contains(Vec2::ZERO, FillRule::NonZero));
+        Ok(())
+    }
+
+    #[test]
+    fn test_distance_to() -> Result<(), ShapeError> {
+        let path = nested_squares()?;
+        assert_eq!(path.distance_to(vec2(1.0, 5.0)), 1.0);
+        assert_eq!(path.distance_to(vec2(5.0, 5.0)), 2.0);
+        assert_eq!(path.distance_to(vec2(13.0, 14.0)), 5.0);
+        let circle = PathBuilder::new(vec2(10.0, 0.0))?
+            .arc_to(Vec2::splat(10.0), 0.0, true, true, vec2(10.0, -0.001))?
+            .build();
+        assert!((circle.distance_to(vec2(3.0, 4.0)) - 5.0).abs() < 0.01);
+        assert_eq!(Path::default().distance_to(Vec2::ZERO), f32::INFINITY);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bounding_boxes() -> Result<(), ShapeError> {
+        let path = nested_squares()?;
+        assert_eq!(
+            path.subpaths()[1].bounding_box(),
+            BoundingBox {
+                min: vec2(3.0, 3.0),
+                max: vec2(7.0, 7.0)
+            }
+        );
+        let bounding_box = path.bounding_box().unwrap();
+        assert!(bounding_box.contains(vec2(10.0, 0.0)));
+        assert!(!bounding_box.contains(vec2(10.1, 0.0)));
+        assert_eq!(bounding_box.distance_to(vec2(5.0, 5.0)), 0.0);
+        assert_eq!(bounding_box.distance_to(vec2(13.0, 14.0)), 5.0);
+        Ok(())
+    }
+}
@@ -1,179 +1,222 @@
-use nannou::prelude::ToPrimitive;
+mod arc;
+mod bezier;
+mod boolean;
+mod edge;
+mod error;
+mod gradient;
+mod hit_test;
+mod measure;
+mod mesh;
+mod nannou_draw;
+mod offset;
+mod path;
+mod polygon;
+mod raster;
+mod shape;
+mod sink;
+mod spline;
+mod stroke;
+mod svg;
+mod svg_parser;
+mod transform;
 
-pub trait ShapeBuilder<Point> {
-    fn add_bezier_curve(self, points: Vec<Point>) -> Self;
-}
+pub use bezier::{BezierCurve, Flattening};
+pub use edge::{NoisyEdge, ScallopedEdge, ZigZagEdge};
+pub use error::ShapeError;
+pub use gradient::{Gradient, GradientShape};
+pub use measure::PathMeasure;
+pub use mesh::{ColoredMesh, Mesh};
+pub use nannou_draw::NannouDrawShapeBuilder;
+pub use path::{BoundingBox, Path, PathBuilder, Subpath};
+pub use polygon::FillRule;
+pub use raster::Rasterizer;
+pub use shape::Shape;
+pub use sink::SinkShapeBuilder;
+pub use stroke::{LineCap, LineJoin, StrokeStyle};
+pub use svg::{SvgDocument, SvgStyle};
+pub use svg_parser::{SvgCommand, SvgPath, SvgPathError};
+pub use transform::TransformShape;
 
-pub trait ShapePath<'a> {
-    fn as_contour(self) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Path>;
-    fn as_fill(self) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Path>;
-}
+use nannou::color::LinSrgba;
 
-pub trait ShapeBuilderFactory<'a, Point, Builder>
-where
-    Builder: ShapeBuilder<Point>,
-{
-    fn start_shape(self, start: Point) -> Builder;
-}
+/// Builds a shape segment by segment.
+///
+/// Operations taking points refuse degenerate input (no points, NaN or
+/// infinite numbers, curves of zero length) with a [ShapeError], so they
+/// chain with `?`.
+pub trait ShapeBuilder<Point>: Sized {
+    /// Adds a Bézier curve starting at the current point.
+    ///
+    /// `points` holds the control points followed by the end point of the curve.
+    fn add_bezier_curve(self, points: Vec<Point>) -> Result<Self, ShapeError>;
 
-pub trait GenerateEdge<Point> {
-    fn generate_edge(self, start: Point) -> dyn Iterator<Item = Point>;
-}
+    /// Starts a new subpath at `to` without connecting it to the current point.
+    ///
+    /// Subpaths are filled together, so one inside another makes a hole
+    /// depending on the fill rule (see [ShapeBuilder::with_fill_rule]).
+    fn move_to(self, to: Point) -> Result<Self, ShapeError>;
 
-pub struct NannouDrawShapeBuilder<'a, T> {
-    draw: nannou::draw::Drawing<'a, T>,
-    points: Vec<nannou::glam::Vec2>,
-}
+    fn line_to(self, to: Point) -> Result<Self, ShapeError> {
+        self.add_bezier_curve(vec![to])
+    }
 
-impl<'a, T> ShapeBuilder<nannou::glam::Vec2> for NannouDrawShapeBuilder<'a, T> {
-    fn add_bezier_curve(self, points: Vec<nannou::glam::Vec2>) -> Self {
-        let mut existing_points = self.points;
-        // println!("existing_points: {:?}", existing_points);
-        let last_point = existing_points.pop().unwrap();
-
-        let mut input = vec![last_point];
-        input.extend(points);
-        // println!("input: {:?}", input);
-        let mut new_points = bezier_curve(input);
-        // println!("new_points: {:?}", new_points);
-        existing_points.append(&mut new_points);
-        return NannouDrawShapeBuilder {
-            draw: self.draw,
-            points: existing_points,
-        };
+    fn quadratic_to(self, ctrl: Point, to: Point) -> Result<Self, ShapeError> {
+        self.add_bezier_curve(vec![ctrl, to])
     }
-}
 
-impl<'a> ShapePath<'a> for NannouDrawShapeBuilder<'a, nannou::draw::primitive::PathInit> {
-    fn as_contour(self) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Path> {
-        let points = self.points;
-        self.draw.stroke().points(points)
+    fn cubic_to(self, ctrl1: Point, ctrl2: Point, to: Point) -> Result<Self, ShapeError> {
+        self.add_bezier_curve(vec![ctrl1, ctrl2, to])
     }
-    fn as_fill(self) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Path> {
-        let points = self.points;
-        self.draw.fill().points(points)
+
+    /// Adds an elliptical arc the same way as the SVG `A` command does,
+    /// except `x_axis_rotation` is given in radians.
+    fn arc_to(
+        self,
+        radii: Point,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    ) -> Result<Self, ShapeError>;
+
+    /// Adds a smooth curve from the current point passing through all `points`.
+    ///
+    /// The curve is a centripetal Catmull-Rom spline, so it neither overshoots
+    /// nor loops between unevenly spaced points. `tension` goes from `0.0`
+    /// (round) to `1.0` (straight lines between the points).
+    fn smooth_through(self, points: Vec<Point>, tension: f32) -> Result<Self, ShapeError>;
+    /// Adds a uniform cubic B-spline from the current point to the last of `points`.
+    ///
+    /// The points in between are control points: the curve is smoother than
+    /// [ShapeBuilder::smooth_through] but only gets close to them.
+    fn b_spline_to(self, points: Vec<Point>) -> Result<Self, ShapeError>;
+    /// Connects the current point back to the start of the current subpath.
+    ///
+    /// Segments added afterwards start a new subpath there, like in SVG.
+    fn close(self) -> Self;
+
+    /// Point where the next segment is going to start.
+    fn current_point(&self) -> Point;
+
+    /// Adds lines following the edge generated between the current point and `to`.
+    ///
+    /// Points of the edge repeating the current point are skipped.
+    fn add_edge<E>(self, edge: &E, to: Point) -> Result<Self, ShapeError>
+    where
+        E: GenerateEdge<Point> + ?Sized,
+        Point: PartialEq,
+    {
+        let from = self.current_point();
+        if to == from {
+            return Err(ShapeError::ZeroLength);
+        }
+        edge.generate_edge(from, to)
+            .try_fold(self, |builder, point| {
+                if point == builder.current_point() {
+                    Ok(builder)
+                } else {
+                    builder.line_to(point)
+                }
+            })
     }
-}
 
-fn bezier_curve<Point>(points: Vec<Point>) -> Vec<Point>
-where
-    Point: std::ops::Mul<f32, Output = Point> + std::ops::Add<Point, Output = Point> + Copy,
-{
-    let nn = points.len();
-    let n = (nn - 1).to_u128().unwrap();
-    let divisions = 10 * n;
-    let delta = 1.0 / divisions as f64;
-    let points = (0..=divisions)
-        .map(|t_delta| t_delta.to_f64().unwrap() * delta)
-        .map(|td| {
-            let mut result = points[0] * (1.0 - td).powi((nn).to_i32().unwrap()).to_f32().unwrap();
-            for i in 1..nn {
-                let i_128 = i.to_u128().unwrap();
-                let w_i = points[i]
-                    * (binomial_coefficient(n, i_128).to_f64().unwrap()
-                        * (1.0 - td).powi((nn - i - 1).to_i32().unwrap())
-                        * td.powi(i.to_i32().unwrap()))
-                    .to_f32()
-                    .unwrap();
-                result = result + w_i;
-            }
-            result
-        });
-    points.collect::<Vec<_>>()
-}
+    /// Sets how curves of the shape are flattened when it gets drawn.
+    fn with_flattening(self, flattening: Flattening) -> Result<Self, ShapeError>;
 
-fn factorial(num: u128) -> u128 {
-    (1..=num).product()
-}
-fn binomial_coefficient(n: u128, i: u128) -> u128 {
-    factorial(n) / factorial(i) / factorial(n - i)
+    /// Sets which parts of overlapping subpaths get filled, [FillRule::NonZero] by default.
+    fn with_fill_rule(self, fill_rule: FillRule) -> Self;
 }
 
-#[cfg(test)]
-mod test {
-    use nannou::lyon::geom::euclid::vec2;
+pub trait ShapePath<'a> {
+    fn as_contour(self) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Path>;
+    /// Draws the contour with the given weight, caps, joins and dashes.
+    fn as_contour_with(
+        self,
+        style: StrokeStyle,
+    ) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Path>;
+    fn as_fill(self) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Path>;
+    /// Fills the shape with a mesh colored by the gradient.
+    fn as_fill_gradient(
+        self,
+        gradient: &Gradient,
+    ) -> nannou::draw::Drawing<'a, nannou::draw::primitive::Mesh>;
+}
 
-    use crate::bezier_curve;
+/// Draws [Shape]s and meshes prepared ahead of time, nothing gets flattened
+/// or tessellated again.
+pub trait DrawShape {
+    /// Strokes the flattened outline of the shape.
+    fn shape_contour(&self, shape: &Shape) -> nannou::draw::Drawing<nannou::draw::primitive::Path>;
+    /// Fills the shape with its triangles, the color is set on the returned drawing.
+    fn shape_fill(&self, shape: &Shape) -> nannou::draw::Drawing<nannou::draw::primitive::Mesh>;
+    /// Draws a mesh with colored vertices, e.g. a [Shape::fill_gradient].
+    fn colored_mesh(
+        &self,
+        mesh: &ColoredMesh,
+    ) -> nannou::draw::Drawing<nannou::draw::primitive::Mesh>;
+}
 
-    #[test]
-    fn test_powi() {
-        assert_eq!((0.0_f32).powi(0), 1.0)
-    }
+/// Output the shapes get drawn to: nannou's `Draw`, a [SvgDocument] or a [Rasterizer].
+///
+/// The same sketch code drawing to a sink puts its picture on screen, in a
+/// vector file or in a headless image. Shapes are built by starting them on
+/// a `&mut` sink, see [SinkShapeBuilder].
+pub trait ShapeSink {
+    /// Covers everything drawn so far with `color`.
+    fn clear(&mut self, color: LinSrgba);
+    /// Fills `path`, flattening its curves with `flattening` where the output needs it.
+    fn fill_path(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        fill_rule: FillRule,
+        color: LinSrgba,
+    );
+    /// Draws the contour of `path` with the weight, caps, joins and dashes of `style`.
+    fn stroke_path(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        style: &StrokeStyle,
+        color: LinSrgba,
+    );
+    /// Draws a mesh with colored vertices, e.g. a gradient fill.
+    fn fill_mesh(&mut self, mesh: &ColoredMesh);
 
-    #[test]
-    fn test_binomial_coefficient() {
-        assert_eq!(super::binomial_coefficient(5, 0), 1);
-        assert_eq!(super::binomial_coefficient(5, 1), 5);
-        assert_eq!(super::binomial_coefficient(5, 2), 10);
-        assert_eq!(super::binomial_coefficient(5, 3), 10);
-        assert_eq!(super::binomial_coefficient(5, 4), 5);
-        assert_eq!(super::binomial_coefficient(5, 5), 1);
-    }
-    #[test]
-    fn test_bezier_curve() {
-        let points = bezier_curve(vec![vec2::<f32, f32>(0.0, 0.0), vec2(0.0, 10.0)]);
-        assert_eq!(
-            points,
-            vec![
-                vec2(0.0, 0.0,),
-                vec2(0.0, 2.0,),
-                vec2(0.0, 4.0,),
-                vec2(0.0, 6.0,),
-                vec2(0.0, 8.0,),
-                vec2(0.0, 10.0)
-            ]
-        );
-    }
-    #[test]
-    fn test_bezier_curve1() {
-        let points = bezier_curve(vec![vec2::<f32, f32>(0.0, 0.0), vec2(150.0, 100.0)]);
-        assert_eq!(
-            points,
-            vec![
-                vec2(0.0, 0.0,),
-                vec2(30.0, 20.0,),
-                vec2(60.0, 40.0,),
-                vec2(90.0, 60.0,),
-                vec2(120.0, 80.0,),
-                vec2(150.0, 100.0)
-            ]
+    /// Fills `path` with `gradient`, as a mesh colored by the gradient unless
+    /// the output has gradients of its own.
+    fn fill_path_gradient(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        fill_rule: FillRule,
+        gradient: &Gradient,
+    ) {
+        self.fill_mesh(
+            &path
+                .fill_mesh(flattening, fill_rule)
+                .with_gradient(gradient),
         );
     }
 
-    #[test]
-    fn test_bezier_curve_quadratic() {
-        let points = bezier_curve(vec![
-            vec2::<f32, f32>(0.0, 0.0),
-            vec2(50.0, 50.0),
-            vec2(100.0, 0.0),
-        ]);
-        assert_eq!(
-            points,
-            vec![
-                vec2(0.0, 0.0,),
-                vec2(20.0, 20.0,),
-                vec2(40.0, 40.0,),
-                vec2(60.0, 40.0,),
-                vec2(80.0, 20.0,),
-                vec2(100.0, 0.0)
-            ]
-        );
+    /// Fills a [Shape] prepared ahead of time.
+    fn fill_shape(&mut self, shape: &Shape, color: LinSrgba) {
+        self.fill_mesh(&shape.fill().with_color(color));
     }
 }
-impl<'a>
-    ShapeBuilderFactory<
-        'a,
-        nannou::glam::Vec2,
-        NannouDrawShapeBuilder<'a, nannou::draw::primitive::PathInit>,
-    > for nannou::draw::Drawing<'a, nannou::draw::primitive::PathInit>
+
+pub trait ShapeBuilderFactory<'a, Point, Builder>
+where
+    Builder: ShapeBuilder<Point>,
 {
-    fn start_shape(
-        self,
-        start: nannou::glam::Vec2,
-    ) -> NannouDrawShapeBuilder<'a, nannou::draw::primitive::PathInit> {
-        NannouDrawShapeBuilder {
-            draw: self,
-            points: vec![start],
-        }
-    }
+    /// Starts a shape at `start`, which has to be finite.
+    fn start_shape(self, start: Point) -> Result<Builder, ShapeError>;
+    /// Continues an already built [Path], e.g. the result of a boolean operation.
+    fn continue_shape(self, path: Path) -> Builder;
+}
+
+/// Procedural edge between two points, see [ShapeBuilder::add_edge].
+pub trait GenerateEdge<Point> {
+    /// Points of the edge from `start` to `end`, without `start` and ending exactly at `end`.
+    fn generate_edge(&self, start: Point, end: Point) -> Box<dyn Iterator<Item = Point> + '_>;
 }
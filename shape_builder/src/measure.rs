@@ -0,0 +1,286 @@
+use nannou::glam::Vec2;
+
+use crate::path::MEASURE_TOLERANCE;
+use crate::{BezierCurve, Path, Subpath};
+
+/// Halvings of the parameter interval between two samples when looking for a distance.
+const BISECTION_STEPS: usize = 20;
+
+/// Arc-length lookup table of a path.
+///
+/// Maps distances along the path to the parameters of its curves, so that
+/// points can be placed at even distances whatever the speed of the curves.
+/// Distances run through all subpaths in order, the gaps between subpaths
+/// are not counted.
+#[derive(Debug, Clone)]
+pub struct PathMeasure<'a> {
+    path: &'a Path,
+    samples: Vec<Sample>,
+}
+
+/// Distance from the start of the path to the point of parameter `t` on a segment.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    length: f32,
+    subpath: usize,
+    segment: usize,
+    t: f32,
+}
+
+impl<'a> PathMeasure<'a> {
+    pub fn new(path: &'a Path) -> Self {
+        let mut samples = vec![];
+        let mut length = 0.0;
+        for (subpath, s) in path.subpaths().iter().enumerate() {
+            for (segment, curve) in s.segments().iter().enumerate() {
+                let mut previous = curve.start();
+                for (t, point) in curve.flatten_parametrized(MEASURE_TOLERANCE) {
+                    length += previous.distance(point);
+                    previous = point;
+                    samples.push(Sample {
+                        length,
+                        subpath,
+                        segment,
+                        t,
+                    });
+                }
+            }
+        }
+        PathMeasure { path, samples }
+    }
+
+    /// Total length of the path.
+    pub fn length(&self) -> f32 {
+        self.samples.last().map_or(0.0, |s| s.length)
+    }
+
+    /// Point at `distance` from the start of the path, `None` for a path without segments.
+    ///
+    /// The distance is clamped to the length of the path.
+    pub fn point_at_length(&self, distance: f32) -> Option<Vec2> {
+        let (curve, t) = self.locate(distance)?;
+        Some(curve.point_at(t))
+    }
+
+    /// Unit vector pointing in the direction of travel at `distance`.
+    pub fn tangent_at_length(&self, distance: f32) -> Option<Vec2> {
+        let (curve, t) = self.locate(distance)?;
+        let tangent = curve.tangent_at(t);
+        if tangent.length_squared() > f32::EPSILON {
+            return Some(tangent.normalize());
+        }
+        // control points on top of an end point cancel the derivative there,
+        // a point slightly inside the curve still has the right direction
+        let inside = if t < 0.5 { t + 1e-3 } else { t - 1e-3 };
+        let direction = (curve.point_at(inside) - curve.point_at(t)) * (inside - t).signum();
+        Some(direction.normalize_or_zero())
+    }
+
+    /// Unit vector perpendicular to the path at `distance`, pointing to the left
+    /// of the direction of travel (inwards on counter-clockwise shapes).
+    pub fn normal_at_length(&self, distance: f32) -> Option<Vec2> {
+        self.tangent_at_length(distance).map(|t| t.perp())
+    }
+
+    /// `n` points evenly spaced along the path, starting at its start.
+    ///
+    /// The last point is the end of the path, unless the path ends with a
+    /// closed subpath where it would repeat the first point: the spacing is
+    /// then `length / n` so that the points loop evenly.
+    pub fn resample_evenly(&self, n: usize) -> Vec<Vec2> {
+        if n == 0 || self.samples.is_empty() {
+            return vec![];
+        }
+        let closed = self.path.subpaths().last().is_some_and(Subpath::is_closed);
+        let intervals = if closed { n } else { (n - 1).max(1) };
+        let step = self.length() / intervals as f32;
+        (0..n)
+            .filter_map(|i| self.point_at_length(step * i as f32))
+            .collect()
+    }
+
+    /// Segment and parameter of the point at `distance`.
+    fn locate(&self, distance: f32) -> Option<(&'a BezierCurve<Vec2>, f32)> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let distance = distance.clamp(0.0, self.length());
+        let i = self
+            .samples
+            .partition_point(|s| s.length < distance)
+            .min(self.samples.len() - 1);
+        let b = self.samples[i];
+        let curve = &self.path.subpaths()[b.subpath].segments()[b.segment];
+        // `b` is the first sample of its segment only when it is at `distance` already
+        let a = match i.checked_sub(1).map(|i| self.samples[i]) {
+            Some(a) if a.subpath == b.subpath && a.segment == b.segment => a,
+            _ => return Some((curve, b.t)),
+        };
+        if curve.degree() <= 1 {
+            let span = b.length - a.length;
+            let fraction = if span > 0.0 {
+                (distance - a.length) / span
+            } else {
+                1.0
+            };
+            return Some((curve, a.t + (b.t - a.t) * fraction));
+        }
+        // the curve is flat between the samples but its speed may still vary,
+        // so `t` is searched for along the chord instead of interpolated
+        let (start, target) = (curve.point_at(a.t), distance - a.length);
+        let (mut low, mut high) = (a.t, b.t);
+        for _ in 0..BISECTION_STEPS {
+            let middle = (low + high) * 0.5;
+            if start.distance(curve.point_at(middle)) < target {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Some((curve, (low + high) * 0.5))
+    }
+}
+
+impl Path {
+    /// Arc-length lookup table of the path, worth keeping when sampling it many times.
+    pub fn measure(&self) -> PathMeasure<'_> {
+        PathMeasure::new(self)
+    }
+
+    /// See [PathMeasure::point_at_length].
+    pub fn point_at_length(&self, distance: f32) -> Option<Vec2> {
+        self.measure().point_at_length(distance)
+    }
+
+    /// See [PathMeasure::normal_at_length].
+    pub fn normal_at_length(&self, distance: f32) -> Option<Vec2> {
+        self.measure().normal_at_length(distance)
+    }
+
+    /// See [PathMeasure::resample_evenly].
+    pub fn resample_evenly(&self, n: usize) -> Vec<Vec2> {
+        self.measure().resample_evenly(n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::{PI, TAU};
+
+    use nannou::glam::{vec2, Vec2};
+
+    use crate::{Path, PathBuilder, ShapeBuilder, ShapeError};
+
+    const RADIUS: f32 = 50.0;
+
+    /// Counter-clockwise circle around the origin, starting on the positive x axis.
+    fn circle() -> Result<Path, ShapeError> {
+        let radii = Vec2::splat(RADIUS);
+        Ok(PathBuilder::new(vec2(RADIUS, 0.0))?
+            .arc_to(radii, 0.0, false, true, vec2(-RADIUS, 0.0))?
+            .arc_to(radii, 0.0, false, true, vec2(RADIUS, 0.0))?
+            .close()
+            .build())
+    }
+
+    fn assert_close(a: Vec2, b: Vec2, epsilon: f32) {
+        assert!(a.distance(b) < epsilon, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_line() -> Result<(), ShapeError> {
+        // the control point bunched up at the start makes `t` a poor guess of the distance
+        let line = PathBuilder::new(vec2(0.0, 0.0))?
+            .cubic_to(vec2(0.0, 0.0), vec2(0.0, 0.0), vec2(100.0, 0.0))?
+            .line_to(vec2(100.0, 50.0))?
+            .build();
+        let measure = line.measure();
+        assert!((measure.length() - 150.0).abs() < 1e-3);
+        assert_close(
+            measure.point_at_length(30.0).unwrap(),
+            vec2(30.0, 0.0),
+            0.05,
+        );
+        assert_close(
+            measure.point_at_length(125.0).unwrap(),
+            vec2(100.0, 25.0),
+            1e-4,
+        );
+        assert_close(
+            measure.normal_at_length(30.0).unwrap(),
+            vec2(0.0, 1.0),
+            1e-4,
+        );
+        assert_close(
+            measure.normal_at_length(125.0).unwrap(),
+            vec2(-1.0, 0.0),
+            1e-4,
+        );
+        // the derivative of the cubic vanishes at its start
+        assert_close(measure.normal_at_length(0.0).unwrap(), vec2(0.0, 1.0), 1e-4);
+        // distances are clamped
+        assert_eq!(measure.point_at_length(-5.0), Some(vec2(0.0, 0.0)));
+        assert_eq!(measure.point_at_length(500.0), Some(vec2(100.0, 50.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_circle() -> Result<(), ShapeError> {
+        let circle = circle()?;
+        let measure = circle.measure();
+        assert!((measure.length() - TAU * RADIUS).abs() < 0.05);
+        for i in 0..16 {
+            let angle = i as f32 * PI / 8.0;
+            let expected = vec2(angle.cos(), angle.sin());
+            let point = measure.point_at_length(angle * RADIUS).unwrap();
+            assert_close(point, expected * RADIUS, 0.05);
+            assert_close(
+                measure.normal_at_length(angle * RADIUS).unwrap(),
+                -expected,
+                2e-3,
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_resample_evenly() -> Result<(), ShapeError> {
+        let line = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(100.0, 0.0))?
+            .build();
+        let points = line.resample_evenly(5);
+        let expected = [0.0, 25.0, 50.0, 75.0, 100.0].map(|x| vec2(x, 0.0));
+        assert_eq!(points, expected);
+        assert_eq!(line.resample_evenly(1), vec![vec2(0.0, 0.0)]);
+        assert!(line.resample_evenly(0).is_empty());
+
+        // a closed path loops without repeating its first point
+        let points = circle()?.resample_evenly(6);
+        assert_eq!(points.len(), 6);
+        for i in 0..6 {
+            let chord = points[i].distance(points[(i + 1) % 6]);
+            assert!((chord - RADIUS).abs() < 0.05, "{}", chord);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_subpaths() -> Result<(), ShapeError> {
+        let lines = [
+            PathBuilder::new(vec2(0.0, 0.0))?.line_to(vec2(10.0, 0.0))?,
+            PathBuilder::new(vec2(0.0, 10.0))?.line_to(vec2(10.0, 10.0))?,
+        ];
+        let path: Path = lines
+            .into_iter()
+            .flat_map(|line| line.build().subpaths().to_vec())
+            .collect();
+        let measure = path.measure();
+        assert_eq!(measure.length(), 20.0);
+        // the gap between the subpaths does not count
+        assert_eq!(measure.point_at_length(10.0), Some(vec2(10.0, 0.0)));
+        assert_eq!(measure.point_at_length(15.0), Some(vec2(5.0, 10.0)));
+        assert_eq!(Path::default().point_at_length(0.0), None);
+        assert!(Path::default().resample_evenly(3).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use nannou::color::{IntoLinSrgba, LinSrgba};
+use nannou::glam::{Affine2, Vec2};
+use nannou::lyon::tessellation::{
+    self, BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions,
+    StrokeTessellator, StrokeVertex, VertexBuffers,
+};
+
+use crate::{FillRule, Flattening, Gradient, LineCap, LineJoin, Path, StrokeStyle, TransformShape};
+
+/// Upper bound of triangles in a subdivided mesh.
+const MAX_TRIANGLES: usize = 1 << 16;
+
+/// Triangle mesh, every three indices into `points` make one triangle.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mesh {
+    pub points: Vec<Vec2>,
+    pub indices: Vec<usize>,
+}
+
+/// Triangle mesh with a color for every vertex.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColoredMesh {
+    pub vertices: Vec<(Vec2, LinSrgba)>,
+    pub indices: Vec<usize>,
+}
+
+impl Path {
+    /// Triangles covering the inside of the path.
+    ///
+    /// Open subpaths are filled as if they were closed. The mesh is empty when
+    /// the path can't be tessellated.
+    pub fn fill_mesh(&self, flattening: Flattening, fill_rule: FillRule) -> Mesh {
+        let mut buffers: VertexBuffers<Vec2, usize> = VertexBuffers::new();
+        let result = FillTessellator::new().tessellate(
+            self.flattened_events(flattening),
+            &fill_options(fill_rule),
+            &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| {
+                let position = vertex.position();
+                Vec2::new(position.x, position.y)
+            }),
+        );
+        match result {
+            Ok(_) => Mesh {
+                points: buffers.vertices,
+                indices: buffers.indices,
+            },
+            Err(_) => Mesh::default(),
+        }
+    }
+
+    /// Triangles covering the contour drawn with `style`, dashes included.
+    ///
+    /// Triangles of a stroke may overlap where it turns sharply.
+    pub fn stroke_mesh(&self, style: &StrokeStyle, flattening: Flattening) -> Mesh {
+        let mut buffers: VertexBuffers<Vec2, usize> = VertexBuffers::new();
+        let result = StrokeTessellator::new().tessellate(
+            self.dashed(style, flattening)
+                .flattened_events(Flattening::default()),
+            &stroke_options(style),
+            &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| {
+                let position = vertex.position();
+                Vec2::new(position.x, position.y)
+            }),
+        );
+        match result {
+            Ok(_) => Mesh {
+                points: buffers.vertices,
+                indices: buffers.indices,
+            },
+            Err(_) => Mesh::default(),
+        }
+    }
+}
+
+/// Options making lyon fill paths with our fill rule.
+pub(crate) fn fill_options(fill_rule: FillRule) -> FillOptions {
+    let fill_rule = match fill_rule {
+        FillRule::NonZero => tessellation::FillRule::NonZero,
+        FillRule::EvenOdd => tessellation::FillRule::EvenOdd,
+    };
+    FillOptions::default().with_fill_rule(fill_rule)
+}
+
+/// Options making lyon stroke paths with our style, dashes aside.
+pub(crate) fn stroke_options(style: &StrokeStyle) -> StrokeOptions {
+    let cap = match style.cap {
+        LineCap::Butt => tessellation::LineCap::Butt,
+        LineCap::Square => tessellation::LineCap::Square,
+        LineCap::Round => tessellation::LineCap::Round,
+    };
+    let join = match style.join {
+        LineJoin::Miter => tessellation::LineJoin::Miter,
+        LineJoin::Round => tessellation::LineJoin::Round,
+        LineJoin::Bevel => tessellation::LineJoin::Bevel,
+    };
+    StrokeOptions::default()
+        .with_line_width(style.weight)
+        .with_line_cap(cap)
+        .with_line_join(join)
+        // lyon panics on miter limits below 1
+        .with_miter_limit(style.miter_limit.max(StrokeOptions::MINIMUM_MITER_LIMIT))
+}
+
+impl Mesh {
+    fn triangles(&self) -> impl Iterator<Item = [Vec2; 3]> + '_ {
+        self.indices.chunks_exact(3).map(|triangle| {
+            [
+                self.points[triangle[0]],
+                self.points[triangle[1]],
+                self.points[triangle[2]],
+            ]
+        })
+    }
+
+    /// Splits every triangle into smaller ones, so that no edge is longer than `max_edge`.
+    ///
+    /// All triangles are split the same number of times, so that edges shared by two of
+    /// them get the same points on both sides. The subdivision stops short of `max_edge`
+    /// rather than making more than [MAX_TRIANGLES] triangles.
+    pub fn subdivided(&self, max_edge: f32) -> Mesh {
+        if max_edge.is_nan() || max_edge <= 0.0 {
+            return self.clone();
+        }
+        let longest = self
+            .triangles()
+            .map(|[a, b, c]| a.distance(b).max(b.distance(c)).max(c.distance(a)))
+            .fold(0.0, f32::max);
+        // every triangle becomes n * n smaller ones
+        let triangle_count = (self.indices.len() / 3).max(1);
+        let max_n = ((MAX_TRIANGLES / triangle_count) as f32).sqrt() as usize;
+        let n = ((longest / max_edge).ceil() as usize).clamp(1, max_n.max(1));
+        if n == 1 {
+            return self.clone();
+        }
+        let mut mesh = Mesh::default();
+        for [a, b, c] in self.triangles() {
+            // grid of points a + (b - a) * i / n + (c - a) * j / n where i + j <= n
+            let mut rows = Vec::with_capacity(n + 1);
+            for i in 0..=n {
+                rows.push(mesh.points.len());
+                for j in 0..=(n - i) {
+                    let (u, v) = (i as f32 / n as f32, j as f32 / n as f32);
+                    mesh.points.push(a + (b - a) * u + (c - a) * v);
+                }
+            }
+            let index = |i: usize, j: usize| rows[i] + j;
+            for i in 0..n {
+                for j in 0..(n - i) {
+                    mesh.indices
+                        .extend([index(i, j), index(i + 1, j), index(i, j + 1)]);
+                    if i + j + 1 < n {
+                        mesh.indices.extend([
+                            index(i + 1, j),
+                            index(i + 1, j + 1),
+                            index(i, j + 1),
+                        ]);
+                    }
+                }
+            }
+        }
+        mesh
+    }
+
+    /// Gives every vertex the same color.
+    pub fn with_color(&self, color: impl IntoLinSrgba<f32>) -> ColoredMesh {
+        let color = color.into_lin_srgba();
+        ColoredMesh {
+            vertices: self.points.iter().map(|&p| (p, color)).collect(),
+            indices: self.indices.clone(),
+        }
+    }
+
+    /// Colors the mesh with `gradient`, subdividing it finely enough to show all the stops.
+    pub fn with_gradient(&self, gradient: &Gradient) -> ColoredMesh {
+        let mesh = self.subdivided(gradient.resolution());
+        ColoredMesh {
+            vertices: mesh
+                .points
+                .iter()
+                .map(|&p| (p, gradient.color_at(p)))
+                .collect(),
+            indices: mesh.indices,
+        }
+    }
+}
+
+impl TransformShape for Mesh {
+    fn transform(mut self, transform: Affine2) -> Self {
+        self.points
+            .iter_mut()
+            .for_each(|p| *p = transform.transform_point2(*p));
+        self
+    }
+}
+
+impl TransformShape for ColoredMesh {
+    fn transform(mut self, transform: Affine2) -> Self {
+        self.vertices
+            .iter_mut()
+            .for_each(|(p, _)| *p = transform.transform_point2(*p));
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::color::LinSrgba;
+    use nannou::glam::{vec2, Vec2};
+
+    use super::{Mesh, MAX_TRIANGLES};
+    use crate::{FillRule, Flattening, Gradient, PathBuilder, ShapeBuilder, ShapeError};
+
+    fn area(mesh: &Mesh) -> f32 {
+        mesh.triangles()
+            .map(|[a, b, c]| 0.5 * (b - a).perp_dot(c - a).abs())
+            .sum()
+    }
+
+    fn square() -> Result<Mesh, ShapeError> {
+        Ok(PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .line_to(vec2(0.0, 10.0))?
+            .close()
+            .build()
+            .fill_mesh(Flattening::default(), FillRule::NonZero))
+    }
+
+    #[test]
+    fn test_fill_rule_holes() -> Result<(), ShapeError> {
+        // both squares go counter-clockwise, only the even-odd rule makes a hole
+        let path = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .line_to(vec2(0.0, 10.0))?
+            .close()
+            .move_to(vec2(3.0, 3.0))?
+            .line_to(vec2(7.0, 3.0))?
+            .line_to(vec2(7.0, 7.0))?
+            .line_to(vec2(3.0, 7.0))?
+            .close()
+            .build();
+        let flattening = Flattening::default();
+        let non_zero = path.fill_mesh(flattening, FillRule::NonZero);
+        let even_odd = path.fill_mesh(flattening, FillRule::EvenOdd);
+        assert!((area(&non_zero) - 100.0).abs() < 1e-3);
+        assert!((area(&even_odd) - 84.0).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_mesh() -> Result<(), ShapeError> {
+        let mesh = square()?;
+        assert_eq!(mesh.points.len(), 4);
+        assert_eq!(mesh.indices.len(), 6);
+        assert!((area(&mesh) - 100.0).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_subdivided() -> Result<(), ShapeError> {
+        let mesh = square()?.subdivided(2.0);
+        // the diagonal of both triangles is split into 8 parts
+        assert_eq!(mesh.indices.len(), 2 * 8 * 8 * 3);
+        assert!((area(&mesh) - 100.0).abs() < 1e-3);
+        for [a, b, c] in mesh.triangles() {
+            assert!(a.distance(b).max(b.distance(c)).max(c.distance(a)) <= 2.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_subdivided_shared_edges() {
+        // a long and a short triangle sharing the edge from (0, 0) to (1, 0)
+        let mesh = Mesh {
+            points: vec![
+                vec2(0.0, 0.0),
+                vec2(1.0, 0.0),
+                vec2(0.0, 10.0),
+                vec2(0.5, -0.5),
+            ],
+            indices: vec![0, 1, 2, 1, 0, 3],
+        }
+        .subdivided(2.0);
+        // no point may lie inside an edge of another triangle, that would leave a crack
+        for [a, b, c] in mesh.triangles() {
+            for (p, q) in [(a, b), (b, c), (c, a)] {
+                for &point in &mesh.points {
+                    let t = (point - p).dot(q - p) / (q - p).length_squared();
+                    let inside = t > 1e-3 && t < 1.0 - 1e-3;
+                    assert!(!inside || point.distance(p.lerp(q, t)) > 1e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_subdivided_max_triangles() -> Result<(), ShapeError> {
+        let mesh = square()?.subdivided(1e-3);
+        assert!(mesh.indices.len() / 3 <= MAX_TRIANGLES);
+        assert!(mesh.indices.len() > 2 * 64 * 64 * 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_gradient() -> Result<(), ShapeError> {
+        let gradient = Gradient::radial(vec2(5.0, 5.0), 5.0)
+            .with_stop(0.0, LinSrgba::new(1.0, 1.0, 1.0, 1.0))
+            .with_stop(1.0, LinSrgba::new(0.0, 0.0, 0.0, 1.0));
+        let mesh = square()?.with_gradient(&gradient);
+        let color_at = |point: Vec2| {
+            mesh.vertices
+                .iter()
+                .find(|(p, _)| p.distance(point) < 1e-3)
+                .map(|(_, color)| *color)
+        };
+        // the middle of the square is a vertex only thanks to the subdivision
+        assert!(color_at(vec2(5.0, 5.0)).unwrap().red > 0.999);
+        assert_eq!(color_at(vec2(0.0, 0.0)).unwrap().red, 0.0);
+        assert!(mesh.indices.iter().all(|&i| i < mesh.vertices.len()));
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use nannou::color::LinSrgba;
+use nannou::draw::primitive::mesh::Vertexless;
+use nannou::draw::primitive::{Mesh as MeshPrimitive, Path as PathPrimitive, PathInit};
+use nannou::draw::{Draw, Drawing};
+use nannou::glam::{Affine2, Vec2};
+
+use crate::mesh::{fill_options, stroke_options};
+use crate::{
+    ColoredMesh, DrawShape, FillRule, Flattening, Gradient, Path, PathBuilder, Shape, ShapeBuilder,
+    ShapeBuilderFactory, ShapeError, ShapePath, ShapeSink, StrokeStyle, TransformShape,
+};
+
+/// [ShapeBuilder] drawing the built [Path] with nannou.
+pub struct NannouDrawShapeBuilder<'a, T> {
+    draw: Drawing<'a, T>,
+    builder: PathBuilder,
+}
+
+impl<'a, T> NannouDrawShapeBuilder<'a, T> {
+    pub fn path(&self) -> &Path {
+        self.builder.path()
+    }
+
+    fn map_builder(
+        self,
+        map: impl FnOnce(PathBuilder) -> Result<PathBuilder, ShapeError>,
+    ) -> Result<Self, ShapeError> {
+        Ok(NannouDrawShapeBuilder {
+            draw: self.draw,
+            builder: map(self.builder)?,
+        })
+    }
+}
+
+impl<'a, T> ShapeBuilder<Vec2> for NannouDrawShapeBuilder<'a, T> {
+    fn add_bezier_curve(self, points: Vec<Vec2>) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.add_bezier_curve(points))
+    }
+
+    fn move_to(self, to: Vec2) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.move_to(to))
+    }
+
+    fn arc_to(
+        self,
+        radii: Vec2,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vec2,
+    ) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.arc_to(radii, x_axis_rotation, large_arc, sweep, to))
+    }
+
+    fn smooth_through(self, points: Vec<Vec2>, tension: f32) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.smooth_through(points, tension))
+    }
+
+    fn b_spline_to(self, points: Vec<Vec2>) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.b_spline_to(points))
+    }
+
+    fn close(self) -> Self {
+        NannouDrawShapeBuilder {
+            builder: self.builder.close(),
+            ..self
+        }
+    }
+
+    fn current_point(&self) -> Vec2 {
+        self.builder.current_point()
+    }
+
+    fn with_flattening(self, flattening: Flattening) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.with_flattening(flattening))
+    }
+
+    fn with_fill_rule(self, fill_rule: FillRule) -> Self {
+        NannouDrawShapeBuilder {
+            builder: self.builder.with_fill_rule(fill_rule),
+            ..self
+        }
+    }
+}
+
+impl<'a, T> TransformShape for NannouDrawShapeBuilder<'a, T> {
+    fn transform(self, transform: Affine2) -> Self {
+        NannouDrawShapeBuilder {
+            builder: self.builder.transform(transform),
+            ..self
+        }
+    }
+}
+
+impl<'a> ShapePath<'a> for NannouDrawShapeBuilder<'a, PathInit> {
+    fn as_contour(self) -> Drawing<'a, PathPrimitive> {
+        let events = self.path().flattened_events(self.builder.flattening());
+        self.draw.stroke().events(events)
+    }
+    fn as_contour_with(self, style: StrokeStyle) -> Drawing<'a, PathPrimitive> {
+        let events = self
+            .path()
+            .dashed(&style, self.builder.flattening())
+            .flattened_events(Flattening::default());
+        self.draw
+            .stroke()
+            .stroke_opts(stroke_options(&style))
+            .events(events)
+    }
+    fn as_fill(self) -> Drawing<'a, PathPrimitive> {
+        let events = self.path().flattened_events(self.builder.flattening());
+        self.draw
+            .fill()
+            .fill_opts(fill_options(self.builder.fill_rule()))
+            .events(events)
+    }
+    fn as_fill_gradient(self, gradient: &Gradient) -> Drawing<'a, MeshPrimitive> {
+        let mesh = self
+            .path()
+            .fill_mesh(self.builder.flattening(), self.builder.fill_rule())
+            .with_gradient(gradient);
+        self.draw.map_ty(|_| Vertexless::default()).indexed_colored(
+            mesh.vertices.into_iter().map(|(p, c)| (p.extend(0.0), c)),
+            mesh.indices,
+        )
+    }
+}
+
+impl DrawShape for Draw {
+    fn shape_contour(&self, shape: &Shape) -> Drawing<PathPrimitive> {
+        let events = shape.outline().flattened_events(Flattening::default());
+        self.path().stroke().events(events)
+    }
+
+    fn shape_fill(&self, shape: &Shape) -> Drawing<MeshPrimitive> {
+        let mesh = shape.fill();
+        self.mesh().indexed(
+            mesh.points.iter().map(|p| p.extend(0.0)),
+            mesh.indices.iter().copied(),
+        )
+    }
+
+    fn colored_mesh(&self, mesh: &ColoredMesh) -> Drawing<MeshPrimitive> {
+        self.mesh().indexed_colored(
+            mesh.vertices.iter().map(|&(p, c)| (p.extend(0.0), c)),
+            mesh.indices.iter().copied(),
+        )
+    }
+}
+
+impl ShapeSink for Draw {
+    fn clear(&mut self, color: LinSrgba) {
+        self.background().color(color);
+    }
+
+    fn fill_path(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        fill_rule: FillRule,
+        color: LinSrgba,
+    ) {
+        self.path()
+            .fill()
+            .fill_opts(fill_options(fill_rule))
+            .events(path.flattened_events(flattening))
+            .color(color);
+    }
+
+    fn stroke_path(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        style: &StrokeStyle,
+        color: LinSrgba,
+    ) {
+        let events = path
+            .dashed(style, flattening)
+            .flattened_events(Flattening::default());
+        self.path()
+            .stroke()
+            .stroke_opts(stroke_options(style))
+            .events(events)
+            .color(color);
+    }
+
+    fn fill_mesh(&mut self, mesh: &ColoredMesh) {
+        self.colored_mesh(mesh);
+    }
+
+    fn fill_shape(&mut self, shape: &Shape, color: LinSrgba) {
+        self.shape_fill(shape).color(color);
+    }
+}
+
+impl<'a> ShapeBuilderFactory<'a, Vec2, NannouDrawShapeBuilder<'a, PathInit>>
+    for Drawing<'a, PathInit>
+{
+    fn start_shape(self, start: Vec2) -> Result<NannouDrawShapeBuilder<'a, PathInit>, ShapeError> {
+        Ok(NannouDrawShapeBuilder {
+            draw: self,
+            builder: PathBuilder::new(start)?,
+        })
+    }
+
+    fn continue_shape(self, path: Path) -> NannouDrawShapeBuilder<'a, PathInit> {
+        NannouDrawShapeBuilder {
+            draw: self,
+            builder: PathBuilder::from(path),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::f32::consts::PI;
+
+use nannou::glam::{vec2, Vec2};
+
+use crate::boolean::resolve;
+use crate::polygon::{rings, winding_number};
+use crate::{Flattening, LineJoin, Path};
+
+/// Longest miter (relative to the offset distance) before it gets beveled.
+const MITER_LIMIT: f32 = 4.0;
+/// Tolerance of round joins when the path is flattened into a fixed number of divisions.
+const ROUND_JOIN_TOLERANCE: f32 = 0.1;
+
+impl Path {
+    /// Outline of the path moved outwards by `distance`, or inwards when it is negative.
+    ///
+    /// Subpaths are treated as closed polygons filled with the non-zero rule,
+    /// `join` shapes the corners that move apart. Parts thinner than twice
+    /// the inset disappear.
+    pub fn offset(&self, distance: f32, join: LineJoin, flattening: Flattening) -> Path {
+        // oriented polygons without overlaps: holes go clockwise and get offset the other way
+        let outline = Path::union_all([self], flattening);
+        if distance == 0.0 {
+            return outline;
+        }
+        let tolerance = match flattening {
+            Flattening::Tolerance(tolerance) => tolerance,
+            Flattening::Divisions(_) => ROUND_JOIN_TOLERANCE,
+        };
+        let offset = rings(&outline, flattening)
+            .iter()
+            .map(|ring| offset_ring(ring, distance, join, tolerance))
+            .collect::<Vec<_>>();
+        resolve(&offset, |point| winding_number(&offset, point) > 0)
+    }
+}
+
+/// Moves every edge of the polygon by `distance` to its right and connects
+/// the moved edges, the result may overlap itself.
+fn offset_ring(ring: &[Vec2], distance: f32, join: LineJoin, tolerance: f32) -> Vec<Vec2> {
+    let normal = |i: usize| {
+        let edge = ring[(i + 1) % ring.len()] - ring[i];
+        vec2(edge.y, -edge.x).normalize() * distance
+    };
+    let mut offset = vec![];
+    for i in 0..ring.len() {
+        let vertex = ring[i];
+        let before = normal((i + ring.len() - 1) % ring.len());
+        let after = normal(i);
+        let turn = before.perp_dot(after).atan2(before.dot(after));
+        if turn * distance.signum() < 0.0 {
+            // the moved edges cross each other, going through the vertex keeps
+            // the loop they make out of the positive winding area
+            offset.extend([vertex + before, vertex, vertex + after]);
+            continue;
+        }
+        let miter = 1.0 / (turn * 0.5).cos();
+        match join {
+            LineJoin::Miter if miter <= MITER_LIMIT => {
+                offset.push(vertex + (before + after).normalize() * distance.abs() * miter);
+            }
+            LineJoin::Round => {
+                let radius = distance.abs();
+                let step = 2.0 * (1.0 - tolerance.min(radius) / radius).acos();
+                let steps = (turn.abs() / step.max(PI / 180.0)).ceil().max(1.0) as usize;
+                offset.extend((0..=steps).map(|s| {
+                    let (sin, cos) = (turn * s as f32 / steps as f32).sin_cos();
+                    vertex
+                        + vec2(
+                            before.x * cos - before.y * sin,
+                            before.x * sin + before.y * cos,
+                        )
+                }));
+            }
+            _ => offset.extend([vertex + before, vertex + after]),
+        }
+    }
+    offset
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::PI;
+
+    use nannou::glam::{vec2, Vec2};
+
+    use crate::polygon::signed_area;
+    use crate::{Flattening, LineJoin, Path, PathBuilder, ShapeBuilder, ShapeError};
+
+    fn polygon(points: &[Vec2]) -> Result<Path, ShapeError> {
+        Ok(points[1..]
+            .iter()
+            .try_fold(PathBuilder::new(points[0])?, |b, p| b.line_to(*p))?
+            .close()
+            .build())
+    }
+
+    fn square(min: Vec2, size: f32) -> Result<Path, ShapeError> {
+        polygon(&[
+            min,
+            min + vec2(size, 0.0),
+            min + vec2(size, size),
+            min + vec2(0.0, size),
+        ])
+    }
+
+    /// Signed area and number of corners of every subpath, sorted by area.
+    fn rings(path: &Path) -> Vec<(f32, usize)> {
+        let mut rings = path
+            .flatten(Flattening::default())
+            .iter()
+            .map(|ring| (signed_area(&ring[1..]), ring.len() - 1))
+            .collect::<Vec<_>>();
+        rings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        rings
+    }
+
+    fn offset(path: &Path, distance: f32, join: LineJoin) -> Vec<(f32, usize)> {
+        rings(&path.offset(distance, join, Flattening::Tolerance(0.01)))
+    }
+
+    #[test]
+    fn test_outset_square() -> Result<(), ShapeError> {
+        let square = square(vec2(0.0, 0.0), 10.0)?;
+        assert_eq!(offset(&square, 1.0, LineJoin::Miter), vec![(144.0, 4)]);
+        assert_eq!(offset(&square, 1.0, LineJoin::Bevel), vec![(142.0, 8)]);
+        let (area, _) = offset(&square, 1.0, LineJoin::Round)[0];
+        assert!((area - (140.0 + PI)).abs() < 0.05, "{}", area);
+        Ok(())
+    }
+
+    #[test]
+    fn test_inset_square() -> Result<(), ShapeError> {
+        let square = square(vec2(0.0, 0.0), 10.0)?;
+        // corners of an inset convex polygon stay sharp whatever the join
+        assert_eq!(offset(&square, -1.0, LineJoin::Round), vec![(64.0, 4)]);
+        assert!(square
+            .offset(-6.0, LineJoin::Miter, Flattening::default())
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_concave_corner() -> Result<(), ShapeError> {
+        let l_shape = polygon(&[
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 5.0),
+            vec2(5.0, 5.0),
+            vec2(5.0, 10.0),
+            vec2(0.0, 10.0),
+        ])?;
+        assert_eq!(offset(&l_shape, 1.0, LineJoin::Miter), vec![(119.0, 6)]);
+        assert_eq!(offset(&l_shape, -1.0, LineJoin::Miter), vec![(39.0, 6)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hole() -> Result<(), ShapeError> {
+        let outer = square(vec2(0.0, 0.0), 10.0)?;
+        let ring = outer.difference(&square(vec2(3.0, 3.0), 4.0)?, Flattening::default());
+        assert_eq!(
+            offset(&ring, 1.0, LineJoin::Miter),
+            vec![(-4.0, 4), (144.0, 4)]
+        );
+        // the hole gets filled
+        assert_eq!(offset(&ring, 2.5, LineJoin::Miter), vec![(225.0, 4)]);
+        assert_eq!(
+            offset(&ring, -1.0, LineJoin::Miter),
+            vec![(-36.0, 4), (64.0, 4)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_miter_limit() -> Result<(), ShapeError> {
+        // the sharp tip would need a miter much longer than the limit
+        let spike = polygon(&[vec2(0.0, 0.0), vec2(100.0, 5.0), vec2(0.0, 10.0)])?;
+        let outset = spike.offset(1.0, LineJoin::Miter, Flattening::default());
+        let bounding_box = outset.bounding_box().unwrap();
+        assert!(bounding_box.max.x < 101.0, "{:?}", bounding_box);
+        Ok(())
+    }
+}
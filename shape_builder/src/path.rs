@@ -0,0 +1,566 @@
+use nannou::glam::{Affine2, Vec2};
+use nannou::lyon::math::point;
+use nannou::lyon::path::PathEvent;
+
+use crate::error::{check_finite, check_length, check_points};
+use crate::{
+    arc, spline, BezierCurve, FillRule, Flattening, ShapeBuilder, ShapeError, TransformShape,
+};
+
+/// Tolerance used when measuring curves (bounding boxes, lengths).
+pub(crate) const MEASURE_TOLERANCE: f32 = 0.01;
+
+/// Axis aligned rectangle enclosing a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl BoundingBox {
+    fn around(point: Vec2) -> Self {
+        BoundingBox {
+            min: point,
+            max: point,
+        }
+    }
+
+    fn include(&mut self, point: Vec2) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+}
+
+/// Continuous sequence of segments, each being a Bézier curve of any degree.
+///
+/// Every segment starts where the previous one ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subpath {
+    start: Vec2,
+    segments: Vec<BezierCurve<Vec2>>,
+    closed: bool,
+}
+
+impl Subpath {
+    pub fn new(start: Vec2) -> Self {
+        Subpath {
+            start,
+            segments: vec![],
+            closed: false,
+        }
+    }
+
+    /// Subpath made of lines connecting `points`.
+    pub(crate) fn from_polyline(points: &[Vec2], closed: bool) -> Self {
+        Subpath {
+            start: points[0],
+            segments: points
+                .windows(2)
+                .map(|w| BezierCurve::from_points(w.to_vec()))
+                .collect(),
+            closed,
+        }
+    }
+
+    pub fn start(&self) -> Vec2 {
+        self.start
+    }
+
+    pub fn end(&self) -> Vec2 {
+        self.segments.last().map_or(self.start, |s| s.end())
+    }
+
+    pub fn segments(&self) -> &[BezierCurve<Vec2>] {
+        &self.segments
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Flattens the subpath into a polyline starting at [Subpath::start].
+    pub fn flatten(&self, flattening: Flattening) -> Vec<Vec2> {
+        let mut points = vec![self.start];
+        for segment in &self.segments {
+            if segment.degree() <= 1 {
+                points.push(segment.end());
+            } else {
+                points.extend(segment.flatten_with(flattening).into_iter().skip(1));
+            }
+        }
+        points
+    }
+
+    /// Smallest rectangle containing the subpath.
+    pub fn bounding_box(&self) -> BoundingBox {
+        let mut points = self
+            .flatten(Flattening::Tolerance(MEASURE_TOLERANCE))
+            .into_iter();
+        let mut bounding_box = BoundingBox::around(points.next().unwrap_or(self.start));
+        points.for_each(|p| bounding_box.include(p));
+        bounding_box
+    }
+
+    pub fn length(&self) -> f32 {
+        self.flatten(Flattening::Tolerance(MEASURE_TOLERANCE))
+            .windows(2)
+            .map(|w| w[0].distance(w[1]))
+            .sum()
+    }
+}
+
+/// Renderer independent description of a shape made of one or more subpaths.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Path {
+    subpaths: Vec<Subpath>,
+}
+
+impl Path {
+    pub fn subpaths(&self) -> &[Subpath] {
+        &self.subpaths
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subpaths.is_empty()
+    }
+
+    /// Flattens every subpath into a separate polyline.
+    pub fn flatten(&self, flattening: Flattening) -> Vec<Vec<Vec2>> {
+        self.subpaths
+            .iter()
+            .map(|s| s.flatten(flattening))
+            .collect()
+    }
+
+    /// Smallest rectangle containing the whole path, `None` for an empty path.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.subpaths
+            .iter()
+            .map(Subpath::bounding_box)
+            .reduce(|mut a, b| {
+                a.include(b.min);
+                a.include(b.max);
+                a
+            })
+    }
+
+    /// Total length of all subpaths.
+    pub fn length(&self) -> f32 {
+        self.subpaths.iter().map(Subpath::length).sum()
+    }
+
+    /// Flattened path as lyon events, ready to be tessellated.
+    pub(crate) fn flattened_events(&self, flattening: Flattening) -> Vec<PathEvent> {
+        let mut events = vec![];
+        for subpath in &self.subpaths {
+            let points = subpath
+                .flatten(flattening)
+                .into_iter()
+                .map(|p| point(p.x, p.y))
+                .collect::<Vec<_>>();
+            events.push(PathEvent::Begin { at: points[0] });
+            events.extend(points.windows(2).map(|w| PathEvent::Line {
+                from: w[0],
+                to: w[1],
+            }));
+            events.push(PathEvent::End {
+                last: points[points.len() - 1],
+                first: points[0],
+                close: subpath.is_closed(),
+            });
+        }
+        events
+    }
+}
+
+impl FromIterator<Subpath> for Path {
+    fn from_iter<I: IntoIterator<Item = Subpath>>(iter: I) -> Self {
+        Path {
+            subpaths: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// [ShapeBuilder] producing a [Path].
+#[derive(Debug, Clone)]
+pub struct PathBuilder {
+    path: Path,
+    flattening: Flattening,
+    fill_rule: FillRule,
+}
+
+impl PathBuilder {
+    /// Starts a path at `start`, which has to be finite.
+    pub fn new(start: Vec2) -> Result<Self, ShapeError> {
+        check_finite(&[start])?;
+        Ok(PathBuilder {
+            path: Path {
+                subpaths: vec![Subpath::new(start)],
+            },
+            flattening: Flattening::default(),
+            fill_rule: FillRule::default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn flattening(&self) -> Flattening {
+        self.flattening
+    }
+
+    pub fn fill_rule(&self) -> FillRule {
+        self.fill_rule
+    }
+
+    pub fn build(self) -> Path {
+        self.path
+    }
+
+    /// Adds the cubic curves arcs and splines are made of.
+    fn add_cubics(self, cubics: Vec<[Vec2; 3]>) -> Result<Self, ShapeError> {
+        cubics
+            .into_iter()
+            .try_fold(self, |builder, [ctrl1, ctrl2, end]| {
+                builder.cubic_to(ctrl1, ctrl2, end)
+            })
+    }
+
+    fn current_subpath(&self) -> &Subpath {
+        &self.path.subpaths[self.path.subpaths.len() - 1]
+    }
+
+    fn current_subpath_mut(&mut self) -> &mut Subpath {
+        let last = self.path.subpaths.len() - 1;
+        &mut self.path.subpaths[last]
+    }
+}
+
+/// Continues building the last subpath of the path, an empty path
+/// gets a subpath starting at the origin.
+impl From<Path> for PathBuilder {
+    fn from(mut path: Path) -> Self {
+        if path.is_empty() {
+            path.subpaths.push(Subpath::new(Vec2::ZERO));
+        }
+        PathBuilder {
+            path,
+            flattening: Flattening::default(),
+            fill_rule: FillRule::default(),
+        }
+    }
+}
+
+impl ShapeBuilder<Vec2> for PathBuilder {
+    fn add_bezier_curve(mut self, points: Vec<Vec2>) -> Result<Self, ShapeError> {
+        check_points(&points)?;
+        check_length(self.current_point(), &points)?;
+        if self.current_subpath().is_closed() {
+            let start = self.current_point();
+            self.path.subpaths.push(Subpath::new(start));
+        }
+        let mut curve = vec![self.current_point()];
+        curve.extend(points);
+        self.current_subpath_mut()
+            .segments
+            .push(BezierCurve::from_points(curve));
+        Ok(self)
+    }
+
+    fn move_to(mut self, to: Vec2) -> Result<Self, ShapeError> {
+        check_finite(&[to])?;
+        let subpath = self.current_subpath_mut();
+        if subpath.segments.is_empty() {
+            // a subpath without segments draws nothing, it is only moved
+            *subpath = Subpath::new(to);
+        } else {
+            self.path.subpaths.push(Subpath::new(to));
+        }
+        Ok(self)
+    }
+
+    fn arc_to(
+        self,
+        radii: Vec2,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vec2,
+    ) -> Result<Self, ShapeError> {
+        check_finite(&[radii, to, Vec2::splat(x_axis_rotation)])?;
+        let from = self.current_point();
+        check_length(from, &[to])?;
+        self.add_cubics(arc::arc_to_cubics(
+            from,
+            radii,
+            x_axis_rotation,
+            large_arc,
+            sweep,
+            to,
+        ))
+    }
+
+    fn smooth_through(self, points: Vec<Vec2>, tension: f32) -> Result<Self, ShapeError> {
+        check_points(&points)?;
+        check_finite(&[Vec2::splat(tension)])?;
+        check_length(self.current_point(), &points)?;
+        let mut through = vec![self.current_point()];
+        through.extend(points);
+        self.add_cubics(spline::catmull_rom_to_cubics(&through, tension))
+    }
+
+    fn b_spline_to(self, points: Vec<Vec2>) -> Result<Self, ShapeError> {
+        check_points(&points)?;
+        check_length(self.current_point(), &points)?;
+        let mut controls = vec![self.current_point()];
+        controls.extend(points);
+        self.add_cubics(spline::b_spline_to_cubics(&controls))
+    }
+
+    fn close(mut self) -> Self {
+        let subpath = self.current_subpath();
+        let (start, end) = (subpath.start(), subpath.end());
+        if start != end {
+            let curve = BezierCurve::from_points(vec![end, start]);
+            self.current_subpath_mut().segments.push(curve);
+        }
+        self.current_subpath_mut().closed = true;
+        self
+    }
+
+    fn current_point(&self) -> Vec2 {
+        self.current_subpath().end()
+    }
+
+    fn with_flattening(self, flattening: Flattening) -> Result<Self, ShapeError> {
+        let flattening = flattening.check()?;
+        Ok(PathBuilder { flattening, ..self })
+    }
+
+    fn with_fill_rule(self, fill_rule: FillRule) -> Self {
+        PathBuilder { fill_rule, ..self }
+    }
+}
+
+impl TransformShape for Subpath {
+    fn transform(self, transform: Affine2) -> Self {
+        Subpath {
+            start: transform.transform_point2(self.start),
+            segments: self
+                .segments
+                .into_iter()
+                .map(|s| s.transform(transform))
+                .collect(),
+            closed: self.closed,
+        }
+    }
+}
+
+impl TransformShape for Path {
+    fn transform(self, transform: Affine2) -> Self {
+        self.subpaths
+            .into_iter()
+            .map(|s| s.transform(transform))
+            .collect()
+    }
+}
+
+/// Transforms the shape built so far, points added afterwards are not transformed.
+impl TransformShape for PathBuilder {
+    fn transform(self, transform: Affine2) -> Self {
+        PathBuilder {
+            path: self.path.transform(transform),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::PI;
+
+    use nannou::glam::{vec2, Vec2};
+
+    use super::{BoundingBox, PathBuilder};
+    use crate::{Flattening, ShapeBuilder, ShapeError, ZigZagEdge};
+
+    fn square() -> Result<PathBuilder, ShapeError> {
+        Ok(PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .line_to(vec2(0.0, 10.0))?
+            .close())
+    }
+
+    #[test]
+    fn test_square() -> Result<(), ShapeError> {
+        let path = square()?.build();
+        assert_eq!(path.subpaths().len(), 1);
+        let subpath = &path.subpaths()[0];
+        assert!(subpath.is_closed());
+        assert_eq!(subpath.segments().len(), 4);
+        assert_eq!(subpath.end(), vec2(0.0, 0.0));
+        assert_eq!(path.length(), 40.0);
+        assert_eq!(
+            path.bounding_box(),
+            Some(BoundingBox {
+                min: vec2(0.0, 0.0),
+                max: vec2(10.0, 10.0)
+            })
+        );
+        assert_eq!(
+            path.flatten(Flattening::default()),
+            vec![vec![
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(10.0, 10.0),
+                vec2(0.0, 10.0),
+                vec2(0.0, 0.0)
+            ]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_close_on_start_point() -> Result<(), ShapeError> {
+        let path = square()?.build();
+        // last explicit line already ended at the start, so no extra segment is added
+        let again = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .line_to(vec2(0.0, 10.0))?
+            .line_to(vec2(0.0, 0.0))?
+            .close()
+            .build();
+        assert_eq!(path, again);
+        Ok(())
+    }
+
+    #[test]
+    fn test_curve_bounding_box() -> Result<(), ShapeError> {
+        let path = PathBuilder::new(vec2(0.0, 0.0))?
+            .quadratic_to(vec2(50.0, 100.0), vec2(100.0, 0.0))?
+            .build();
+        let bounding_box = path.bounding_box().unwrap();
+        assert_eq!(bounding_box.min, Vec2::ZERO);
+        assert!((bounding_box.max - vec2(100.0, 50.0)).length() < 0.02);
+        Ok(())
+    }
+
+    #[test]
+    fn test_circle_length() -> Result<(), ShapeError> {
+        let path = PathBuilder::new(vec2(50.0, 0.0))?
+            .arc_to(vec2(50.0, 50.0), 0.0, false, true, vec2(-50.0, 0.0))?
+            .arc_to(vec2(50.0, 50.0), 0.0, false, true, vec2(50.0, 0.0))?
+            .close()
+            .build();
+        assert!((path.length() - 100.0 * PI).abs() < 0.1);
+        let bounding_box = path.bounding_box().unwrap();
+        assert!((bounding_box.size() - vec2(100.0, 100.0)).length() < 0.1);
+        assert!(bounding_box.center().length() < 0.1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_smooth_curves() -> Result<(), ShapeError> {
+        let points = vec![vec2(10.0, 10.0), vec2(20.0, 0.0), vec2(30.0, 10.0)];
+        let path = PathBuilder::new(vec2(0.0, 0.0))?
+            .smooth_through(points.clone(), 0.0)?
+            .b_spline_to(points)?
+            .build();
+        let segments = path.subpaths()[0].segments();
+        assert_eq!(segments.len(), 6);
+        assert!(segments.iter().all(|s| s.degree() == 3));
+        assert_eq!(segments[2].end(), vec2(30.0, 10.0));
+        // the B-spline starts where the Catmull-Rom spline ended
+        assert_eq!(segments[3].start(), vec2(30.0, 10.0));
+        assert_eq!(path.subpaths()[0].end(), vec2(30.0, 10.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_subpaths() -> Result<(), ShapeError> {
+        let path = PathBuilder::new(vec2(5.0, 5.0))?
+            .move_to(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .move_to(vec2(0.0, 5.0))?
+            .line_to(vec2(10.0, 5.0))?
+            .close()
+            // continues from the start of the closed subpath
+            .line_to(vec2(0.0, 10.0))?
+            .build();
+        let subpaths = path.subpaths();
+        assert_eq!(subpaths.len(), 3);
+        assert_eq!(subpaths[0].start(), vec2(0.0, 0.0));
+        assert!(!subpaths[0].is_closed());
+        assert!(subpaths[1].is_closed());
+        assert_eq!(subpaths[2].start(), vec2(0.0, 5.0));
+        assert_eq!(subpaths[2].end(), vec2(0.0, 10.0));
+        assert!(!subpaths[2].is_closed());
+        Ok(())
+    }
+
+    #[test]
+    fn test_degenerate_input() -> Result<(), ShapeError> {
+        let start = || PathBuilder::new(vec2(1.0, 1.0));
+        let (nan, infinity) = (vec2(f32::NAN, 0.0), vec2(0.0, f32::INFINITY));
+        assert_eq!(
+            start()?.add_bezier_curve(vec![]).err(),
+            Some(ShapeError::NoPoints)
+        );
+        assert_eq!(
+            start()?.smooth_through(vec![], 0.0).err(),
+            Some(ShapeError::NoPoints)
+        );
+        assert_eq!(
+            start()?.b_spline_to(vec![]).err(),
+            Some(ShapeError::NoPoints)
+        );
+
+        assert_eq!(PathBuilder::new(nan).err(), Some(ShapeError::NotFinite));
+        assert_eq!(start()?.line_to(nan).err(), Some(ShapeError::NotFinite));
+        assert_eq!(
+            start()?.move_to(infinity).err(),
+            Some(ShapeError::NotFinite)
+        );
+        let arc = start()?.arc_to(nan, 0.0, false, false, vec2(5.0, 5.0));
+        assert_eq!(arc.err(), Some(ShapeError::NotFinite));
+        let spline = start()?.smooth_through(vec![vec2(5.0, 5.0)], f32::NAN);
+        assert_eq!(spline.err(), Some(ShapeError::NotFinite));
+
+        let here = vec2(1.0, 1.0);
+        assert_eq!(start()?.line_to(here).err(), Some(ShapeError::ZeroLength));
+        let cubic = start()?.cubic_to(here, here, here);
+        assert_eq!(cubic.err(), Some(ShapeError::ZeroLength));
+        let arc = start()?.arc_to(Vec2::splat(5.0), 0.0, false, false, here);
+        assert_eq!(arc.err(), Some(ShapeError::ZeroLength));
+        let edge = start()?.add_edge(&ZigZagEdge::new(3, 1.0), here);
+        assert_eq!(edge.err(), Some(ShapeError::ZeroLength));
+
+        assert_eq!(
+            start()?.with_flattening(Flattening::Divisions(0)).err(),
+            Some(ShapeError::InvalidFlattening(Flattening::Divisions(0)))
+        );
+        assert!(start()?
+            .with_flattening(Flattening::Tolerance(f32::NAN))
+            .is_err());
+        assert!(start()?
+            .with_flattening(Flattening::Tolerance(0.0))
+            .is_err());
+
+        // splines skip repeated points rather than making curves without length
+        let builder = start()?.smooth_through(vec![here, vec2(5.0, 1.0), vec2(5.0, 1.0)], 0.0)?;
+        assert_eq!(builder.path().subpaths()[0].segments().len(), 1);
+        assert_eq!(builder.current_point(), vec2(5.0, 1.0));
+        Ok(())
+    }
+}
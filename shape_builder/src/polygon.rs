@@ -0,0 +1,121 @@
+use nannou::glam::Vec2;
+
+use crate::{Flattening, Path};
+
+/// Rule deciding which points are inside a shape whose outline crosses itself
+/// or has subpaths inside each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    /// Inside when the outline winds around the point at least once in total,
+    /// so holes need to go the opposite way of their outer outline.
+    #[default]
+    NonZero,
+    /// Inside when a ray from the point crosses the outline an odd number of
+    /// times, so nested subpaths make holes whatever their orientation.
+    EvenOdd,
+}
+
+impl FillRule {
+    /// Whether a point with the given winding number is inside.
+    pub(crate) fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Flattens every subpath of `path` into a closed polygon.
+///
+/// The closing point is not repeated and polygons without area
+/// (less than 3 points) are skipped.
+pub(crate) fn rings(path: &Path, flattening: Flattening) -> Vec<Vec<Vec2>> {
+    path.subpaths()
+        .iter()
+        .filter_map(|subpath| {
+            let mut ring = subpath.flatten(flattening);
+            ring.dedup();
+            if ring.len() > 1 && ring[0] == ring[ring.len() - 1] {
+                ring.pop();
+            }
+            (ring.len() >= 3).then_some(ring)
+        })
+        .collect()
+}
+
+/// Number of times the polygons wind around `point`, counter-clockwise being positive.
+pub(crate) fn winding_number(rings: &[Vec<Vec2>], point: Vec2) -> i32 {
+    let mut winding = 0;
+    for ring in rings {
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            let side = (b - a).perp_dot(point - a);
+            if a.y <= point.y {
+                if b.y > point.y && side > 0.0 {
+                    winding += 1;
+                }
+            } else if b.y <= point.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    winding
+}
+
+/// Area of the polygon, positive when its points go counter-clockwise.
+#[cfg(test)]
+pub(crate) fn signed_area(ring: &[Vec2]) -> f32 {
+    let sum: f32 = ring
+        .iter()
+        .enumerate()
+        .map(|(i, a)| a.perp_dot(ring[(i + 1) % ring.len()]))
+        .sum();
+    sum * 0.5
+}
+
+#[cfg(test)]
+mod test {
+    use std::slice;
+
+    use nannou::glam::vec2;
+
+    use super::{rings, signed_area, winding_number};
+    use crate::{Flattening, Path, Subpath};
+
+    #[test]
+    fn test_rings() {
+        // builders refuse lines without length, squashed shapes still have them
+        let points = [
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 0.0),
+        ];
+        let path: Path = [Subpath::from_polyline(&points, true)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            rings(&path, Flattening::default()),
+            vec![vec![vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(10.0, 10.0)]]
+        );
+    }
+
+    #[test]
+    fn test_winding_and_area() {
+        let square = vec![
+            vec2(0.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(0.0, 10.0),
+        ];
+        let mut clockwise = square.clone();
+        clockwise.reverse();
+        assert_eq!(signed_area(&square), 100.0);
+        assert_eq!(signed_area(&clockwise), -100.0);
+        assert_eq!(winding_number(slice::from_ref(&square), vec2(5.0, 5.0)), 1);
+        assert_eq!(winding_number(&[clockwise], vec2(5.0, 5.0)), -1);
+        assert_eq!(winding_number(slice::from_ref(&square), vec2(15.0, 5.0)), 0);
+        assert_eq!(winding_number(&[square.clone(), square], vec2(5.0, 5.0)), 2);
+    }
+}
@@ -0,0 +1,331 @@
+use std::mem;
+
+use nannou::color::LinSrgba;
+use nannou::glam::{vec2, Vec2};
+use nannou::image::{ImageResult, Rgba, RgbaImage};
+
+use crate::svg::srgb_component;
+use crate::{ColoredMesh, FillRule, Flattening, Mesh, Path, ShapeSink, StrokeStyle};
+
+/// Samples per pixel along each axis unless set with [Rasterizer::with_supersampling].
+const DEFAULT_SUPERSAMPLING: u32 = 2;
+
+/// Picture drawn on the CPU, to render sketches without a window or a GPU.
+///
+/// The picture uses the same coordinates as nannou: the origin is in the
+/// middle and the y axis points up. Colors are blended in linear space and
+/// every pixel averages a grid of samples, which smooths the edges.
+#[derive(Debug, Clone)]
+pub struct Rasterizer {
+    width: u32,
+    height: u32,
+    supersampling: u32,
+    /// Premultiplied colors of the samples, row by row from the top.
+    samples: Vec<[f32; 4]>,
+}
+
+impl Rasterizer {
+    /// Transparent picture of `width` by `height` pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        Rasterizer {
+            width,
+            height,
+            supersampling: DEFAULT_SUPERSAMPLING,
+            samples: vec![[0.0; 4]; samples(width, height, DEFAULT_SUPERSAMPLING)],
+        }
+    }
+
+    /// Sets the samples per pixel along each axis, `1` turns antialiasing off.
+    ///
+    /// The picture is cleared.
+    pub fn with_supersampling(self, supersampling: u32) -> Self {
+        let supersampling = supersampling.max(1);
+        Rasterizer {
+            supersampling,
+            samples: vec![[0.0; 4]; samples(self.width, self.height, supersampling)],
+            ..self
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Color of a pixel, `(0, 0)` being the top left corner like in images.
+    pub fn pixel(&self, x: u32, y: u32) -> LinSrgba {
+        let s = self.supersampling;
+        let row = (self.width * s) as usize;
+        let mut sum = [0.0; 4];
+        for j in y * s..(y + 1) * s {
+            for i in x * s..(x + 1) * s {
+                let sample = self.samples[j as usize * row + i as usize];
+                sum.iter_mut().zip(sample).for_each(|(sum, c)| *sum += c);
+            }
+        }
+        let [r, g, b, a] = sum.map(|c| c / (s * s) as f32);
+        if a <= 0.0 {
+            return LinSrgba::new(0.0, 0.0, 0.0, 0.0);
+        }
+        LinSrgba::new(r / a, g / a, b / a, a)
+    }
+
+    /// The picture with 8 bit sRGB colors.
+    pub fn to_image(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let color = self.pixel(x, y);
+            Rgba([
+                srgb_component(color.red),
+                srgb_component(color.green),
+                srgb_component(color.blue),
+                (color.alpha.clamp(0.0, 1.0) * 255.0).round() as u8,
+            ])
+        })
+    }
+
+    /// Saves the picture in the format of the file extension, e.g. PNG.
+    pub fn save(&self, file: impl AsRef<std::path::Path>) -> ImageResult<()> {
+        self.to_image().save(file)
+    }
+
+    fn fill_triangles(&mut self, mesh: &Mesh, color: LinSrgba) {
+        for triangle in mesh.indices.chunks_exact(3) {
+            self.fill_triangle([0, 1, 2].map(|i| (mesh.points[triangle[i]], color)));
+        }
+    }
+
+    /// Blends the samples whose center is inside the triangle, interpolating
+    /// the colors of the vertices.
+    ///
+    /// Samples on an edge shared by two triangles are only covered by one of
+    /// them (the top-left rule of GPUs), so meshes have no visible seams.
+    fn fill_triangle(&mut self, vertices: [(Vec2, LinSrgba); 3]) {
+        let s = self.supersampling as f32;
+        let (width, height) = (self.width as f32, self.height as f32);
+        let [a, mut b, mut c] = vertices.map(|(p, color)| {
+            (
+                vec2((p.x + 0.5 * width) * s, (0.5 * height - p.y) * s),
+                color,
+            )
+        });
+        let area = edge(a.0, b.0, c.0);
+        if !area.is_finite() || area == 0.0 {
+            return;
+        }
+        if area < 0.0 {
+            mem::swap(&mut b, &mut c);
+        }
+        let area = area.abs();
+        let min = a.0.min(b.0).min(c.0).floor().max(Vec2::ZERO);
+        let max = a.0.max(b.0).max(c.0).ceil();
+        let (columns, rows) = (
+            self.width * self.supersampling,
+            self.height * self.supersampling,
+        );
+        for j in min.y as u32..(max.y as u32).min(rows) {
+            for i in min.x as u32..(max.x as u32).min(columns) {
+                let p = vec2(i as f32 + 0.5, j as f32 + 0.5);
+                let (wa, wb, wc) = (edge(b.0, c.0, p), edge(c.0, a.0, p), edge(a.0, b.0, p));
+                if !(covers(wa, b.0, c.0) && covers(wb, c.0, a.0) && covers(wc, a.0, b.0)) {
+                    continue;
+                }
+                let mix =
+                    |f: fn(&LinSrgba) -> f32| (f(&a.1) * wa + f(&b.1) * wb + f(&c.1) * wc) / area;
+                let alpha = mix(|c| c.alpha).clamp(0.0, 1.0);
+                let source = [mix(|c| c.red), mix(|c| c.green), mix(|c| c.blue)];
+                let sample = &mut self.samples[(j * columns + i) as usize];
+                for (k, channel) in source.into_iter().enumerate() {
+                    sample[k] = channel * alpha + sample[k] * (1.0 - alpha);
+                }
+                sample[3] = alpha + sample[3] * (1.0 - alpha);
+            }
+        }
+    }
+}
+
+impl ShapeSink for Rasterizer {
+    fn clear(&mut self, color: LinSrgba) {
+        let alpha = color.alpha.clamp(0.0, 1.0);
+        let sample = [
+            color.red * alpha,
+            color.green * alpha,
+            color.blue * alpha,
+            alpha,
+        ];
+        self.samples.fill(sample);
+    }
+
+    fn fill_path(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        fill_rule: FillRule,
+        color: LinSrgba,
+    ) {
+        self.fill_triangles(&path.fill_mesh(flattening, fill_rule), color);
+    }
+
+    fn stroke_path(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        style: &StrokeStyle,
+        color: LinSrgba,
+    ) {
+        self.fill_triangles(&path.stroke_mesh(style, flattening), color);
+    }
+
+    fn fill_mesh(&mut self, mesh: &ColoredMesh) {
+        for triangle in mesh.indices.chunks_exact(3) {
+            self.fill_triangle([0, 1, 2].map(|i| mesh.vertices[triangle[i]]));
+        }
+    }
+}
+
+fn samples(width: u32, height: u32, supersampling: u32) -> usize {
+    (width * supersampling) as usize * (height * supersampling) as usize
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`, positive when `p` is
+/// on the right of `a` to `b` with the y axis pointing down.
+fn edge(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    (b - a).perp_dot(p - a)
+}
+
+/// Whether a sample at `weight` from the edge `from` to `to` of a clockwise
+/// triangle is inside: samples exactly on the edge belong to top and left edges.
+fn covers(weight: f32, from: Vec2, to: Vec2) -> bool {
+    let d = to - from;
+    weight > 0.0 || (weight == 0.0 && ((d.y == 0.0 && d.x > 0.0) || d.y < 0.0))
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::color::LinSrgba;
+    use nannou::glam::vec2;
+
+    use super::Rasterizer;
+    use crate::{
+        FillRule, Flattening, Gradient, PathBuilder, ShapeBuilder, ShapeBuilderFactory, ShapeError,
+        ShapeSink, StrokeStyle,
+    };
+
+    fn white() -> LinSrgba {
+        LinSrgba::new(1.0, 1.0, 1.0, 1.0)
+    }
+
+    fn assert_color(actual: LinSrgba, expected: LinSrgba) {
+        let difference = [
+            actual.red - expected.red,
+            actual.green - expected.green,
+            actual.blue - expected.blue,
+            actual.alpha - expected.alpha,
+        ];
+        assert!(
+            difference.iter().all(|d| d.abs() < 1e-4),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_fill() -> Result<(), ShapeError> {
+        let mut picture = Rasterizer::new(10, 10);
+        picture.clear(white());
+        // two triangles sharing a diagonal, blended once on it
+        (&mut picture)
+            .start_shape(vec2(-5.0, -5.0))?
+            .line_to(vec2(0.0, -5.0))?
+            .line_to(vec2(0.0, 5.0))?
+            .line_to(vec2(-5.0, 5.0))?
+            .close()
+            .fill(LinSrgba::new(1.0, 0.0, 0.0, 0.5));
+        let half_red = LinSrgba::new(1.0, 0.5, 0.5, 1.0);
+        for y in 0..10 {
+            for x in 0..5 {
+                assert_color(picture.pixel(x, y), half_red);
+            }
+            for x in 5..10 {
+                assert_color(picture.pixel(x, y), white());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_antialiasing() -> Result<(), ShapeError> {
+        let mut picture = Rasterizer::new(4, 4).with_supersampling(4);
+        // covers the first column and the left half of the second one
+        let path = PathBuilder::new(vec2(-2.0, -2.0))?
+            .line_to(vec2(-0.5, -2.0))?
+            .line_to(vec2(-0.5, 2.0))?
+            .line_to(vec2(-2.0, 2.0))?
+            .close()
+            .build();
+        picture.fill_path(&path, Flattening::default(), FillRule::NonZero, white());
+        assert_color(picture.pixel(0, 0), white());
+        assert_color(picture.pixel(0, 3), white());
+        assert_color(picture.pixel(2, 0), LinSrgba::new(0.0, 0.0, 0.0, 0.0));
+        assert!((picture.pixel(1, 1).alpha - 0.5).abs() < 1e-6);
+        assert_eq!(picture.to_image().get_pixel(1, 1).0, [255, 255, 255, 128]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_even_odd_hole_and_stroke() -> Result<(), ShapeError> {
+        let mut picture = Rasterizer::new(20, 20).with_supersampling(1);
+        (&mut picture)
+            .start_shape(vec2(-8.0, -8.0))?
+            .line_to(vec2(8.0, -8.0))?
+            .line_to(vec2(8.0, 8.0))?
+            .line_to(vec2(-8.0, 8.0))?
+            .close()
+            .move_to(vec2(-4.0, -4.0))?
+            .line_to(vec2(4.0, -4.0))?
+            .line_to(vec2(4.0, 4.0))?
+            .line_to(vec2(-4.0, 4.0))?
+            .close()
+            .with_fill_rule(FillRule::EvenOdd)
+            .fill(white());
+        assert_eq!(picture.pixel(10, 10).alpha, 0.0);
+        assert_eq!(picture.pixel(10, 3).alpha, 1.0);
+        assert_eq!(picture.pixel(10, 0).alpha, 0.0);
+
+        let blue = LinSrgba::new(0.0, 0.0, 1.0, 1.0);
+        (&mut picture)
+            .start_shape(vec2(-10.0, 9.0))?
+            .line_to(vec2(10.0, 9.0))?
+            .stroke(&StrokeStyle::new(2.0), blue);
+        for x in 0..20 {
+            assert_color(picture.pixel(x, 0), blue);
+            assert_color(picture.pixel(x, 1), blue);
+        }
+        assert_color(picture.pixel(10, 2), white());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gradient() -> Result<(), ShapeError> {
+        let mut picture = Rasterizer::new(8, 1).with_supersampling(1);
+        let gradient = Gradient::linear(vec2(-4.0, 0.0), vec2(4.0, 0.0))
+            .with_stop(0.0, LinSrgba::new(0.0, 0.0, 0.0, 1.0))
+            .with_stop(1.0, white());
+        (&mut picture)
+            .start_shape(vec2(-4.0, -0.5))?
+            .line_to(vec2(4.0, -0.5))?
+            .line_to(vec2(4.0, 0.5))?
+            .line_to(vec2(-4.0, 0.5))?
+            .close()
+            .fill_gradient(&gradient);
+        for x in 0..8 {
+            let expected = (x as f32 + 0.5) / 8.0;
+            assert!((picture.pixel(x, 0).red - expected).abs() < 1e-3);
+        }
+        assert_eq!(picture.pixel(0, 0).alpha, 1.0);
+        Ok(())
+    }
+}
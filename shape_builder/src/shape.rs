@@ -0,0 +1,137 @@
+use nannou::glam::Affine2;
+
+use crate::{
+    ColoredMesh, FillRule, Flattening, Gradient, Mesh, Path, PathBuilder, Subpath, TransformShape,
+};
+
+/// Path flattened and tessellated once, to be drawn again and again.
+///
+/// Building a path, flattening its curves and tessellating it every frame is
+/// wasted work when only its position, rotation or size change. A shape keeps
+/// the results, moving them with the transforms of nannou's `Draw` (or
+/// [TransformShape]) never flattens anything again.
+#[derive(Debug, Clone)]
+pub struct Shape {
+    path: Path,
+    outline: Path,
+    fill: Mesh,
+    fill_rule: FillRule,
+}
+
+impl Shape {
+    pub fn new(path: Path, flattening: Flattening, fill_rule: FillRule) -> Self {
+        let outline = path
+            .subpaths()
+            .iter()
+            .map(|s| Subpath::from_polyline(&s.flatten(flattening), s.is_closed()))
+            .collect();
+        let fill = path.fill_mesh(flattening, fill_rule);
+        Shape {
+            path,
+            outline,
+            fill,
+            fill_rule,
+        }
+    }
+
+    /// The path with its curves, e.g. for hit testing.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Flattened outline, made of lines only.
+    pub fn outline(&self) -> &Path {
+        &self.outline
+    }
+
+    /// Triangles covering the inside of the shape.
+    pub fn fill(&self) -> &Mesh {
+        &self.fill
+    }
+
+    /// Rule the fill was tessellated with.
+    pub fn fill_rule(&self) -> FillRule {
+        self.fill_rule
+    }
+
+    /// Fill colored by `gradient`, worth keeping as long as the gradient does not change.
+    pub fn fill_gradient(&self, gradient: &Gradient) -> ColoredMesh {
+        self.fill.with_gradient(gradient)
+    }
+}
+
+/// Shape of the built path, flattened and filled the way the builder would draw it.
+impl From<PathBuilder> for Shape {
+    fn from(builder: PathBuilder) -> Self {
+        let (flattening, fill_rule) = (builder.flattening(), builder.fill_rule());
+        Shape::new(builder.build(), flattening, fill_rule)
+    }
+}
+
+impl TransformShape for Shape {
+    fn transform(self, transform: Affine2) -> Self {
+        Shape {
+            path: self.path.transform(transform),
+            outline: self.outline.transform(transform),
+            fill: self.fill.transform(transform),
+            fill_rule: self.fill_rule,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use nannou::glam::{vec2, Vec2};
+
+    use super::Shape;
+    use crate::{FillRule, Flattening, PathBuilder, ShapeBuilder, ShapeError, TransformShape};
+
+    fn half_disc() -> Result<Shape, ShapeError> {
+        Ok(PathBuilder::new(vec2(-10.0, 0.0))?
+            .arc_to(Vec2::splat(10.0), 0.0, false, false, vec2(10.0, 0.0))?
+            .close()
+            .with_flattening(Flattening::Tolerance(0.1))?
+            .into())
+    }
+
+    #[test]
+    fn test_flattened_once() -> Result<(), ShapeError> {
+        let shape = half_disc()?;
+        let outline = &shape.outline().subpaths()[0];
+        assert!(outline.is_closed());
+        assert!(outline.segments().len() > 4);
+        assert!(outline.segments().iter().all(|s| s.degree() == 1));
+        assert_eq!(
+            shape.outline().flatten(Flattening::default()),
+            shape.path().flatten(Flattening::Tolerance(0.1))
+        );
+        assert!(!shape.fill().indices.is_empty());
+        assert!(shape
+            .fill()
+            .points
+            .iter()
+            .all(|p| p.length() <= 10.01 && p.y >= 0.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_transform_keeps_tessellation() -> Result<(), ShapeError> {
+        let shape = half_disc()?;
+        let moved = shape
+            .clone()
+            .rotate(FRAC_PI_2)
+            .scale(2.0)
+            .translate(vec2(5.0, 0.0));
+        assert_eq!(moved.fill().indices, shape.fill().indices);
+        for (p, q) in shape.fill().points.iter().zip(&moved.fill().points) {
+            let expected = vec2(-p.y, p.x) * 2.0 + vec2(5.0, 0.0);
+            assert!(q.distance(expected) < 1e-4, "{:?} != {:?}", q, expected);
+        }
+        // the curves move along, hit testing works on the moved shape
+        assert!(moved.path().contains(vec2(-5.0, 0.0), FillRule::NonZero));
+        assert!(!moved.path().contains(vec2(15.0, 0.0), FillRule::NonZero));
+        Ok(())
+    }
+}
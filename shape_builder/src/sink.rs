@@ -0,0 +1,175 @@
+use nannou::color::IntoLinSrgba;
+use nannou::glam::{Affine2, Vec2};
+
+use crate::{
+    FillRule, Flattening, Gradient, Path, PathBuilder, ShapeBuilder, ShapeBuilderFactory,
+    ShapeError, ShapeSink, StrokeStyle, TransformShape,
+};
+
+/// [ShapeBuilder] drawing the built [Path] to a [ShapeSink].
+pub struct SinkShapeBuilder<'a, S: ?Sized> {
+    sink: &'a mut S,
+    builder: PathBuilder,
+}
+
+impl<'a, S: ?Sized> SinkShapeBuilder<'a, S> {
+    pub fn path(&self) -> &Path {
+        self.builder.path()
+    }
+
+    fn map_builder(
+        self,
+        map: impl FnOnce(PathBuilder) -> Result<PathBuilder, ShapeError>,
+    ) -> Result<Self, ShapeError> {
+        Ok(SinkShapeBuilder {
+            sink: self.sink,
+            builder: map(self.builder)?,
+        })
+    }
+}
+
+impl<'a, S: ShapeSink + ?Sized> SinkShapeBuilder<'a, S> {
+    pub fn fill(self, color: impl IntoLinSrgba<f32>) {
+        let (flattening, fill_rule) = (self.builder.flattening(), self.builder.fill_rule());
+        self.sink.fill_path(
+            self.builder.path(),
+            flattening,
+            fill_rule,
+            color.into_lin_srgba(),
+        );
+    }
+
+    pub fn stroke(self, style: &StrokeStyle, color: impl IntoLinSrgba<f32>) {
+        let flattening = self.builder.flattening();
+        self.sink.stroke_path(
+            self.builder.path(),
+            flattening,
+            style,
+            color.into_lin_srgba(),
+        );
+    }
+
+    pub fn fill_gradient(self, gradient: &Gradient) {
+        let (flattening, fill_rule) = (self.builder.flattening(), self.builder.fill_rule());
+        self.sink
+            .fill_path_gradient(self.builder.path(), flattening, fill_rule, gradient);
+    }
+}
+
+impl<'a, S: ?Sized> ShapeBuilder<Vec2> for SinkShapeBuilder<'a, S> {
+    fn add_bezier_curve(self, points: Vec<Vec2>) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.add_bezier_curve(points))
+    }
+
+    fn move_to(self, to: Vec2) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.move_to(to))
+    }
+
+    fn arc_to(
+        self,
+        radii: Vec2,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vec2,
+    ) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.arc_to(radii, x_axis_rotation, large_arc, sweep, to))
+    }
+
+    fn smooth_through(self, points: Vec<Vec2>, tension: f32) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.smooth_through(points, tension))
+    }
+
+    fn b_spline_to(self, points: Vec<Vec2>) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.b_spline_to(points))
+    }
+
+    fn close(self) -> Self {
+        SinkShapeBuilder {
+            builder: self.builder.close(),
+            ..self
+        }
+    }
+
+    fn current_point(&self) -> Vec2 {
+        self.builder.current_point()
+    }
+
+    fn with_flattening(self, flattening: Flattening) -> Result<Self, ShapeError> {
+        self.map_builder(|b| b.with_flattening(flattening))
+    }
+
+    fn with_fill_rule(self, fill_rule: FillRule) -> Self {
+        SinkShapeBuilder {
+            builder: self.builder.with_fill_rule(fill_rule),
+            ..self
+        }
+    }
+}
+
+impl<'a, S: ?Sized> TransformShape for SinkShapeBuilder<'a, S> {
+    fn transform(self, transform: Affine2) -> Self {
+        SinkShapeBuilder {
+            builder: self.builder.transform(transform),
+            ..self
+        }
+    }
+}
+
+impl<'a, S: ShapeSink + ?Sized> ShapeBuilderFactory<'a, Vec2, SinkShapeBuilder<'a, S>>
+    for &'a mut S
+{
+    fn start_shape(self, start: Vec2) -> Result<SinkShapeBuilder<'a, S>, ShapeError> {
+        Ok(SinkShapeBuilder {
+            sink: self,
+            builder: PathBuilder::new(start)?,
+        })
+    }
+
+    fn continue_shape(self, path: Path) -> SinkShapeBuilder<'a, S> {
+        SinkShapeBuilder {
+            sink: self,
+            builder: PathBuilder::from(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::color::LinSrgba;
+    use nannou::glam::vec2;
+
+    use crate::{
+        ShapeBuilder, ShapeBuilderFactory, ShapeError, ShapeSink, StrokeStyle, SvgDocument,
+        TransformShape,
+    };
+
+    /// Same drawing code for every sink.
+    fn draw_sketch<S: ShapeSink + ?Sized>(sink: &mut S) -> Result<(), ShapeError> {
+        sink.clear(LinSrgba::new(1.0, 1.0, 1.0, 1.0));
+        sink.start_shape(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(0.0, 10.0))?
+            .close()
+            .translate(vec2(5.0, 5.0))
+            .fill(LinSrgba::new(1.0, 0.0, 0.0, 1.0));
+        sink.start_shape(vec2(-20.0, -20.0))?
+            .line_to(vec2(20.0, -20.0))?
+            .stroke(&StrokeStyle::new(2.0), LinSrgba::new(0.0, 0.0, 1.0, 1.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_svg_sink() -> Result<(), ShapeError> {
+        let mut document = SvgDocument::new(100.0, 100.0);
+        draw_sketch(&mut document)?;
+        let svg = document.to_string();
+        assert!(svg
+            .contains("<rect x=\"-50\" y=\"-50\" width=\"100\" height=\"100\" fill=\"#ffffff\"/>"));
+        assert!(svg.contains("<path d=\"M5,5 L15,5 L5,15 Z\" fill=\"#ff0000\" stroke=\"none\"/>"));
+        assert!(svg.contains(
+            "<path d=\"M-20,-20 L20,-20\" fill=\"none\" stroke=\"#0000ff\" stroke-width=\"2\"/>"
+        ));
+        Ok(())
+    }
+}
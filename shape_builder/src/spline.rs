@@ -0,0 +1,167 @@
+use nannou::glam::Vec2;
+
+/// Converts a centripetal Catmull-Rom spline passing through `points` into
+/// cubic Bézier segments.
+///
+/// `tension` shortens the tangents, `0.0` keeps the Catmull-Rom curve and
+/// `1.0` makes straight lines. Every returned item holds the two control
+/// points and the end point of one segment, the first segment starts at `points[0]`.
+pub(crate) fn catmull_rom_to_cubics(points: &[Vec2], tension: f32) -> Vec<[Vec2; 3]> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if points.len() < 2 {
+        return vec![];
+    }
+    let n = points.len();
+    // mirrored neighbours at both ends keep the end tangents pointing to the next point
+    let point = |i: isize| match i {
+        -1 => 2.0 * points[0] - points[1],
+        i if i as usize == n => 2.0 * points[n - 1] - points[n - 2],
+        i => points[i as usize],
+    };
+    let scale = (1.0 - tension) / 3.0;
+    (0..n as isize - 1)
+        .map(|i| {
+            let [p0, p1, p2, p3] = [point(i - 1), point(i), point(i + 1), point(i + 2)];
+            // square roots of the distances make the parametrization centripetal
+            let d0 = p0.distance(p1).sqrt();
+            let d1 = p1.distance(p2).sqrt();
+            let d2 = p2.distance(p3).sqrt();
+            let t1 = d1 * ((p1 - p0) / d0 - (p2 - p0) / (d0 + d1)) + (p2 - p1);
+            let t2 = d1 * ((p3 - p2) / d2 - (p3 - p1) / (d1 + d2)) + (p2 - p1);
+            [p1 + t1 * scale, p2 - t2 * scale, p2]
+        })
+        .collect()
+}
+
+/// Converts a uniform cubic B-spline into cubic Bézier segments.
+///
+/// The curve starts at the first and ends at the last of the control `points`,
+/// but only gets close to the other ones. Items are laid out like the ones of
+/// [catmull_rom_to_cubics].
+pub(crate) fn b_spline_to_cubics(points: &[Vec2]) -> Vec<[Vec2; 3]> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if points.len() < 2 {
+        return vec![];
+    }
+    let n = points.len();
+    // mirrored control points make the curve end at the first and last points
+    let mut controls = Vec::with_capacity(n + 2);
+    controls.push(2.0 * points[0] - points[1]);
+    controls.extend(&points);
+    controls.push(2.0 * points[n - 1] - points[n - 2]);
+    controls
+        .windows(4)
+        .map(|w| {
+            [
+                (2.0 * w[1] + w[2]) / 3.0,
+                (w[1] + 2.0 * w[2]) / 3.0,
+                (w[1] + 4.0 * w[2] + w[3]) / 6.0,
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::glam::{vec2, Vec2};
+
+    use super::{b_spline_to_cubics, catmull_rom_to_cubics};
+    use crate::BezierCurve;
+
+    fn zig_zag() -> Vec<Vec2> {
+        vec![
+            vec2(0.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(30.0, 0.0),
+            vec2(35.0, 10.0),
+            vec2(80.0, 0.0),
+        ]
+    }
+
+    fn curves(start: Vec2, segments: &[[Vec2; 3]]) -> Vec<BezierCurve<Vec2>> {
+        let mut from = start;
+        segments
+            .iter()
+            .map(|[c1, c2, to]| {
+                let curve = BezierCurve::from_points(vec![from, *c1, *c2, *to]);
+                from = *to;
+                curve
+            })
+            .collect()
+    }
+
+    fn assert_smooth(curves: &[BezierCurve<Vec2>]) {
+        for pair in curves.windows(2) {
+            let (a, b) = (pair[0].tangent_at(1.0), pair[1].tangent_at(0.0));
+            assert!(a.normalize().perp_dot(b.normalize()).abs() < 1e-4);
+            assert!(a.dot(b) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_catmull_rom_passes_through_points() {
+        let points = zig_zag();
+        let segments = catmull_rom_to_cubics(&points, 0.0);
+        assert_eq!(segments.len(), 4);
+        for (segment, point) in segments.iter().zip(&points[1..]) {
+            assert_eq!(segment[2], *point);
+        }
+        assert_smooth(&curves(points[0], &segments));
+    }
+
+    #[test]
+    fn test_catmull_rom_tension() {
+        let points = zig_zag();
+        let segments = catmull_rom_to_cubics(&points, 1.0);
+        assert_eq!(segments[0], [points[0], points[1], points[1]]);
+        // collinear points make a straight line whatever the tension
+        let line = [vec2(0.0, 0.0), vec2(1.0, 1.0), vec2(5.0, 5.0)];
+        for [c1, c2, _] in catmull_rom_to_cubics(&line, 0.5) {
+            assert!((c1.x - c1.y).abs() < 1e-5 && (c2.x - c2.y).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_catmull_rom_short_segment() {
+        // with a uniform parametrization, the long neighbours would make
+        // the short segment in the middle overshoot into a loop
+        let points = [
+            vec2(0.0, 0.0),
+            vec2(1.0, 0.0),
+            vec2(1.0, 0.1),
+            vec2(50.0, 0.1),
+        ];
+        let curves = curves(points[0], &catmull_rom_to_cubics(&points, 0.0));
+        let mut previous = curves[1].start();
+        for i in 1..=20 {
+            let p = curves[1].point_at(i as f32 * 0.05);
+            assert!(p.y > previous.y && (p.x - 1.0).abs() < 0.02, "{:?}", p);
+            previous = p;
+        }
+    }
+
+    #[test]
+    fn test_b_spline() {
+        let points = zig_zag();
+        let segments = b_spline_to_cubics(&points);
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[3][2], points[4]);
+        let curves = curves(points[0], &segments);
+        assert_smooth(&curves);
+        // the curve is pulled towards the inner control points without reaching them
+        let middle = curves[1].start();
+        assert!(middle.y > 2.0 && middle.y < 10.0, "{:?}", middle);
+    }
+
+    #[test]
+    fn test_degenerated() {
+        assert!(catmull_rom_to_cubics(&[vec2(1.0, 1.0)], 0.0).is_empty());
+        assert!(b_spline_to_cubics(&[vec2(1.0, 1.0), vec2(1.0, 1.0)]).is_empty());
+        assert_eq!(
+            catmull_rom_to_cubics(&[vec2(0.0, 0.0), vec2(0.0, 0.0), vec2(3.0, 0.0)], 0.0).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::mem;
+
+use nannou::glam::Vec2;
+
+use crate::{Flattening, Path, Subpath};
+
+/// Shape of the ends of open subpaths and dashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Square,
+    Round,
+}
+
+/// Shape of the corners between segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// How the contour of a shape is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub weight: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Longest miter (relative to the weight) before it gets beveled.
+    pub miter_limit: f32,
+    /// Alternating lengths of dashes and gaps, the contour is solid when empty.
+    ///
+    /// Like in SVG, a pattern of odd length is repeated to make it even.
+    pub dashes: Vec<f32>,
+    /// Distance into the dash pattern at which every subpath starts.
+    pub dash_offset: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        StrokeStyle {
+            weight: 1.0,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: 4.0,
+            dashes: vec![],
+            dash_offset: 0.0,
+        }
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(weight: f32) -> Self {
+        StrokeStyle {
+            weight,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_cap(self, cap: LineCap) -> Self {
+        StrokeStyle { cap, ..self }
+    }
+
+    pub fn with_join(self, join: LineJoin) -> Self {
+        StrokeStyle { join, ..self }
+    }
+
+    pub fn with_miter_limit(self, miter_limit: f32) -> Self {
+        StrokeStyle {
+            miter_limit,
+            ..self
+        }
+    }
+
+    pub fn with_dashes(self, dashes: Vec<f32>, dash_offset: f32) -> Self {
+        StrokeStyle {
+            dashes,
+            dash_offset,
+            ..self
+        }
+    }
+
+    /// Dash pattern of an even length, `None` when the contour is solid.
+    fn dash_pattern(&self) -> Option<Vec<f32>> {
+        let valid = self.dashes.iter().all(|d| d.is_finite() && *d >= 0.0)
+            && self.dashes.iter().sum::<f32>() > 0.0;
+        if !valid {
+            return None;
+        }
+        let mut pattern = self.dashes.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(&self.dashes);
+        }
+        Some(pattern)
+    }
+}
+
+impl Path {
+    /// Splits the path into open polylines, one for every dash of the `style`.
+    ///
+    /// Dashes are measured along the flattened path and the pattern restarts
+    /// with every subpath. Without dashes the path is only flattened.
+    pub fn dashed(&self, style: &StrokeStyle, flattening: Flattening) -> Path {
+        let pattern = style.dash_pattern();
+        let mut subpaths = vec![];
+        for subpath in self.subpaths() {
+            let points = subpath.flatten(flattening);
+            match &pattern {
+                Some(pattern) => subpaths.extend(
+                    dash_polyline(&points, pattern, style.dash_offset)
+                        .iter()
+                        .map(|dash| Subpath::from_polyline(dash, false)),
+                ),
+                None => subpaths.push(Subpath::from_polyline(&points, subpath.is_closed())),
+            }
+        }
+        subpaths.into_iter().collect()
+    }
+}
+
+/// Cuts a polyline into dashes following an even, non-empty `pattern`.
+fn dash_polyline(points: &[Vec2], pattern: &[f32], offset: f32) -> Vec<Vec<Vec2>> {
+    let total: f32 = pattern.iter().sum();
+    let mut index = 0;
+    let mut remaining = pattern[0];
+    let mut skip = offset.rem_euclid(total);
+    while skip >= remaining {
+        skip -= remaining;
+        index = (index + 1) % pattern.len();
+        remaining = pattern[index];
+    }
+    remaining -= skip;
+
+    let mut dashes = vec![];
+    let mut dash = if index % 2 == 0 {
+        vec![points[0]]
+    } else {
+        vec![]
+    };
+    for w in points.windows(2) {
+        let (mut from, to) = (w[0], w[1]);
+        let mut length = from.distance(to);
+        while length >= remaining {
+            let split = if length > 0.0 {
+                from.lerp(to, remaining / length)
+            } else {
+                to
+            };
+            dash.push(split);
+            if index % 2 == 0 {
+                dashes.push(mem::take(&mut dash));
+            }
+            length -= remaining;
+            from = split;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= length;
+        if index % 2 == 0 && length > 0.0 {
+            dash.push(to);
+        }
+    }
+    if dash.len() > 1 {
+        dashes.push(dash);
+    }
+    dashes
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::glam::{vec2, Vec2};
+
+    use super::StrokeStyle;
+    use crate::{Flattening, Path, PathBuilder, ShapeBuilder, ShapeError};
+
+    fn dashes(path: &Path) -> Vec<Vec<Vec2>> {
+        path.flatten(Flattening::default())
+    }
+
+    fn line() -> Result<Path, ShapeError> {
+        Ok(PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .build())
+    }
+
+    #[test]
+    fn test_solid() -> Result<(), ShapeError> {
+        let square = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .close()
+            .build();
+        let solid = square.dashed(&StrokeStyle::new(2.0), Flattening::default());
+        assert_eq!(solid, square);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dashes_follow_corners() -> Result<(), ShapeError> {
+        let style = StrokeStyle::new(1.0).with_dashes(vec![4.0, 2.0], 0.0);
+        let path = line()?.dashed(&style, Flattening::default());
+        assert!(path.subpaths().iter().all(|s| !s.is_closed()));
+        assert_eq!(
+            dashes(&path),
+            vec![
+                vec![vec2(0.0, 0.0), vec2(4.0, 0.0)],
+                vec![vec2(6.0, 0.0), vec2(10.0, 0.0)],
+                vec![vec2(10.0, 2.0), vec2(10.0, 6.0)],
+                vec![vec2(10.0, 8.0), vec2(10.0, 10.0)],
+            ]
+        );
+        assert_eq!(path.length(), 14.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dash_offset() -> Result<(), ShapeError> {
+        let style = StrokeStyle::new(1.0).with_dashes(vec![4.0, 2.0], -1.0);
+        let path = line()?.dashed(&style, Flattening::default());
+        assert_eq!(dashes(&path)[0], vec![vec2(1.0, 0.0), vec2(5.0, 0.0)]);
+        assert_eq!(
+            dashes(&path)[1],
+            vec![vec2(7.0, 0.0), vec2(10.0, 0.0), vec2(10.0, 1.0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_odd_pattern_is_repeated() -> Result<(), ShapeError> {
+        let style = StrokeStyle::new(1.0).with_dashes(vec![3.0], 0.0);
+        let path = line()?.dashed(&style, Flattening::default());
+        // dashes of 3 separated by gaps of 3
+        assert_eq!(path.subpaths().len(), 4);
+        assert_eq!(path.length(), 11.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dashed_circle_length() -> Result<(), ShapeError> {
+        let circle = PathBuilder::new(vec2(50.0, 0.0))?
+            .arc_to(vec2(50.0, 50.0), 0.0, false, true, vec2(-50.0, 0.0))?
+            .arc_to(vec2(50.0, 50.0), 0.0, false, true, vec2(50.0, 0.0))?
+            .close()
+            .build();
+        let style = StrokeStyle::new(1.0).with_dashes(vec![10.0, 10.0], 0.0);
+        let dashed = circle.dashed(&style, Flattening::Tolerance(0.01));
+        // half of the circumference is covered by dashes
+        assert!((dashed.length() - circle.length() * 0.5).abs() < 5.0);
+        assert_eq!(dashed.subpaths().len(), 16);
+        Ok(())
+    }
+}
@@ -0,0 +1,572 @@
+use std::fmt::{self, Display, Write};
+
+use nannou::color::{IntoLinSrgba, LinSrgba};
+
+use crate::{
+    ColoredMesh, FillRule, Flattening, Gradient, GradientShape, LineCap, LineJoin, Path, Shape,
+    ShapeSink, StrokeStyle, Subpath,
+};
+
+/// Tolerance used for curves that have no SVG command (degree higher than 3).
+const SVG_TOLERANCE: f32 = 0.1;
+
+impl Path {
+    /// SVG path data (the `d` attribute of `<path>`) describing this path.
+    ///
+    /// Lines, quadratic and cubic curves are written with `L`, `Q` and `C`
+    /// commands, curves of a higher degree are flattened.
+    pub fn to_svg_path_data(&self) -> String {
+        let mut data = String::new();
+        for subpath in self.subpaths() {
+            let start = subpath.start();
+            write!(data, "M{},{}", number(start.x), number(start.y)).unwrap();
+            let segments = subpath.segments();
+            for (i, segment) in segments.iter().enumerate() {
+                let closing_line = subpath.is_closed()
+                    && i + 1 == segments.len()
+                    && segment.degree() == 1
+                    && segment.end() == start;
+                if closing_line {
+                    break;
+                }
+                match segment.points() {
+                    [_, to] => write!(data, " L{},{}", number(to.x), number(to.y)),
+                    [_, ctrl, to] => write!(
+                        data,
+                        " Q{},{} {},{}",
+                        number(ctrl.x),
+                        number(ctrl.y),
+                        number(to.x),
+                        number(to.y)
+                    ),
+                    [_, ctrl1, ctrl2, to] => write!(
+                        data,
+                        " C{},{} {},{} {},{}",
+                        number(ctrl1.x),
+                        number(ctrl1.y),
+                        number(ctrl2.x),
+                        number(ctrl2.y),
+                        number(to.x),
+                        number(to.y)
+                    ),
+                    _ => segment
+                        .flatten_with(Flattening::Tolerance(SVG_TOLERANCE))
+                        .iter()
+                        .skip(1)
+                        .try_for_each(|p| write!(data, " L{},{}", number(p.x), number(p.y))),
+                }
+                .unwrap();
+            }
+            if subpath.is_closed() {
+                data.push_str(" Z");
+            }
+            data.push(' ');
+        }
+        data.pop();
+        data
+    }
+}
+
+/// Fill and stroke of a path written to a [SvgDocument].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgStyle {
+    pub fill: Option<LinSrgba>,
+    pub stroke: Option<LinSrgba>,
+    pub stroke_weight: f32,
+    pub fill_rule: FillRule,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f32,
+}
+
+impl SvgStyle {
+    pub fn fill(color: impl IntoLinSrgba<f32>) -> Self {
+        SvgStyle {
+            fill: Some(color.into_lin_srgba()),
+            stroke: None,
+            stroke_weight: 1.0,
+            fill_rule: FillRule::default(),
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: 4.0,
+        }
+    }
+
+    pub fn stroke(color: impl IntoLinSrgba<f32>, weight: f32) -> Self {
+        SvgStyle {
+            fill: None,
+            stroke: Some(color.into_lin_srgba()),
+            stroke_weight: weight,
+            fill_rule: FillRule::default(),
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: 4.0,
+        }
+    }
+
+    pub fn with_stroke(self, color: impl IntoLinSrgba<f32>, weight: f32) -> Self {
+        SvgStyle {
+            stroke: Some(color.into_lin_srgba()),
+            stroke_weight: weight,
+            ..self
+        }
+    }
+
+    /// Strokes with the weight, caps and joins of `style`.
+    ///
+    /// Dashes are not part of the style, [Path::dashed] cuts them out of the path.
+    pub fn with_stroke_style(self, color: impl IntoLinSrgba<f32>, style: &StrokeStyle) -> Self {
+        SvgStyle {
+            stroke: Some(color.into_lin_srgba()),
+            stroke_weight: style.weight,
+            cap: style.cap,
+            join: style.join,
+            miter_limit: style.miter_limit,
+            ..self
+        }
+    }
+
+    pub fn with_fill_rule(self, fill_rule: FillRule) -> Self {
+        SvgStyle { fill_rule, ..self }
+    }
+}
+
+/// SVG document made of paths.
+///
+/// The document uses the same coordinates as nannou: the origin is in
+/// the middle of the picture and the y axis points up.
+#[derive(Debug, Clone)]
+pub struct SvgDocument {
+    width: f32,
+    height: f32,
+    background: Option<LinSrgba>,
+    /// Gradients referenced by the elements, written in `<defs>`.
+    definitions: Vec<String>,
+    elements: Vec<String>,
+}
+
+impl SvgDocument {
+    pub fn new(width: f32, height: f32) -> Self {
+        SvgDocument {
+            width,
+            height,
+            background: None,
+            definitions: vec![],
+            elements: vec![],
+        }
+    }
+
+    pub fn with_background(self, color: impl IntoLinSrgba<f32>) -> Self {
+        SvgDocument {
+            background: Some(color.into_lin_srgba()),
+            ..self
+        }
+    }
+
+    pub fn add_path(&mut self, path: &Path, style: SvgStyle) {
+        let mut element = format!("<path d=\"{}\"", path.to_svg_path_data());
+        write_paint(&mut element, "fill", style.fill);
+        if style.fill.is_some() && style.fill_rule == FillRule::EvenOdd {
+            element.push_str(" fill-rule=\"evenodd\"");
+        }
+        write_paint(&mut element, "stroke", style.stroke);
+        if style.stroke.is_some() {
+            write!(element, " stroke-width=\"{}\"", number(style.stroke_weight)).unwrap();
+            match style.cap {
+                LineCap::Butt => {}
+                LineCap::Square => element.push_str(" stroke-linecap=\"square\""),
+                LineCap::Round => element.push_str(" stroke-linecap=\"round\""),
+            }
+            match style.join {
+                LineJoin::Miter if style.miter_limit != 4.0 => write!(
+                    element,
+                    " stroke-miterlimit=\"{}\"",
+                    number(style.miter_limit.max(1.0))
+                )
+                .unwrap(),
+                LineJoin::Miter => {}
+                LineJoin::Round => element.push_str(" stroke-linejoin=\"round\""),
+                LineJoin::Bevel => element.push_str(" stroke-linejoin=\"bevel\""),
+            }
+        }
+        element.push_str("/>");
+        self.elements.push(element);
+    }
+
+    /// Fills `path` with `gradient`, which gets added to the definitions of the document.
+    pub fn add_gradient_path(&mut self, path: &Path, fill_rule: FillRule, gradient: &Gradient) {
+        // SVG paints a linear gradient without length with its last stop, not the first one
+        if let GradientShape::Linear { start, end } = gradient.shape {
+            if start == end {
+                let color = gradient.color_at(start);
+                self.add_path(path, SvgStyle::fill(color).with_fill_rule(fill_rule));
+                return;
+            }
+        }
+        let id = format!("gradient{}", self.definitions.len());
+        self.definitions.push(gradient_element(&id, gradient));
+        let mut element = format!(
+            "<path d=\"{}\" fill=\"url(#{})\"",
+            path.to_svg_path_data(),
+            id
+        );
+        if fill_rule == FillRule::EvenOdd {
+            element.push_str(" fill-rule=\"evenodd\"");
+        }
+        element.push_str(" stroke=\"none\"/>");
+        self.elements.push(element);
+    }
+
+    pub fn save(&self, file: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(file, self.to_string())
+    }
+}
+
+/// Curves are written as curves, `flattening` only matters for dashes.
+impl ShapeSink for SvgDocument {
+    fn clear(&mut self, color: LinSrgba) {
+        self.definitions.clear();
+        self.elements.clear();
+        self.background = Some(color);
+    }
+
+    fn fill_path(
+        &mut self,
+        path: &Path,
+        _flattening: Flattening,
+        fill_rule: FillRule,
+        color: LinSrgba,
+    ) {
+        self.add_path(path, SvgStyle::fill(color).with_fill_rule(fill_rule));
+    }
+
+    fn stroke_path(
+        &mut self,
+        path: &Path,
+        flattening: Flattening,
+        style: &StrokeStyle,
+        color: LinSrgba,
+    ) {
+        let svg_style = SvgStyle {
+            fill: None,
+            ..SvgStyle::fill(color).with_stroke_style(color, style)
+        };
+        if style.dashes.is_empty() {
+            self.add_path(path, svg_style);
+        } else {
+            self.add_path(&path.dashed(style, flattening), svg_style);
+        }
+    }
+
+    /// SVG has no colors per vertex, every triangle gets the average color of its vertices.
+    ///
+    /// Gradient fills go through [ShapeSink::fill_path_gradient] and keep their gradient.
+    fn fill_mesh(&mut self, mesh: &ColoredMesh) {
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = triangle.iter().map(|&i| mesh.vertices[i]);
+            let points = vertices.clone().map(|(p, _)| p).collect::<Vec<_>>();
+            let color = vertices.fold(LinSrgba::new(0.0, 0.0, 0.0, 0.0), |sum, (_, c)| {
+                LinSrgba::new(
+                    sum.red + c.red / 3.0,
+                    sum.green + c.green / 3.0,
+                    sum.blue + c.blue / 3.0,
+                    sum.alpha + c.alpha / 3.0,
+                )
+            });
+            let triangle = Path::from_iter([Subpath::from_polyline(&points, true)]);
+            self.add_path(&triangle, SvgStyle::fill(color));
+        }
+    }
+
+    fn fill_path_gradient(
+        &mut self,
+        path: &Path,
+        _flattening: Flattening,
+        fill_rule: FillRule,
+        gradient: &Gradient,
+    ) {
+        self.add_gradient_path(path, fill_rule, gradient);
+    }
+
+    /// Writes the curves of the shape rather than its triangles.
+    fn fill_shape(&mut self, shape: &Shape, color: LinSrgba) {
+        self.add_path(
+            shape.path(),
+            SvgStyle::fill(color).with_fill_rule(shape.fill_rule()),
+        );
+    }
+}
+
+impl Display for SvgDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (w, h) = (number(self.width), number(self.height));
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">",
+            w,
+            h,
+            number(-0.5 * self.width),
+            number(-0.5 * self.height),
+            w,
+            h
+        )?;
+        if let Some(background) = self.background {
+            let mut rect = format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+                number(-0.5 * self.width),
+                number(-0.5 * self.height),
+                w,
+                h
+            );
+            write_paint(&mut rect, "fill", Some(background));
+            writeln!(f, "{}/>", rect)?;
+        }
+        if !self.definitions.is_empty() {
+            writeln!(f, "<defs>")?;
+            for definition in &self.definitions {
+                writeln!(f, "{}", definition)?;
+            }
+            writeln!(f, "</defs>")?;
+        }
+        writeln!(f, "<g transform=\"scale(1,-1)\">")?;
+        for element in &self.elements {
+            writeln!(f, "{}", element)?;
+        }
+        writeln!(f, "</g>")?;
+        writeln!(f, "</svg>")
+    }
+}
+
+/// `<linearGradient>` or `<radialGradient>` element, in the coordinates of the paths.
+///
+/// The colors are interpolated in linear RGB like [Gradient::color_at].
+fn gradient_element(id: &str, gradient: &Gradient) -> String {
+    let (tag, mut element) = match gradient.shape {
+        GradientShape::Linear { start, end } => (
+            "linearGradient",
+            format!(
+                "<linearGradient id=\"{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"",
+                id,
+                number(start.x),
+                number(start.y),
+                number(end.x),
+                number(end.y)
+            ),
+        ),
+        GradientShape::Radial { center, radius } => (
+            "radialGradient",
+            format!(
+                "<radialGradient id=\"{}\" cx=\"{}\" cy=\"{}\" r=\"{}\"",
+                id,
+                number(center.x),
+                number(center.y),
+                number(radius.abs())
+            ),
+        ),
+    };
+    element.push_str(" gradientUnits=\"userSpaceOnUse\" color-interpolation=\"linearRGB\">");
+    for &(offset, color) in gradient.stops() {
+        write!(element, "<stop offset=\"{}\"", number(offset)).unwrap();
+        write_paint(&mut element, "stop-color", Some(color));
+        element.push_str("/>");
+    }
+    write!(element, "</{}>", tag).unwrap();
+    element
+}
+
+fn write_paint(element: &mut String, attribute: &str, color: Option<LinSrgba>) {
+    match color {
+        Some(color) => {
+            write!(
+                element,
+                " {}=\"#{:02x}{:02x}{:02x}\"",
+                attribute,
+                srgb_component(color.red),
+                srgb_component(color.green),
+                srgb_component(color.blue)
+            )
+            .unwrap();
+            if color.alpha < 1.0 {
+                // fill-opacity, stroke-opacity but stop-opacity for stop-color
+                write!(
+                    element,
+                    " {}-opacity=\"{}\"",
+                    attribute.strip_suffix("-color").unwrap_or(attribute),
+                    number(color.alpha)
+                )
+                .unwrap();
+            }
+        }
+        None => write!(element, " {}=\"none\"", attribute).unwrap(),
+    }
+}
+
+/// Converts a linear color component into a 8 bit sRGB value.
+pub(crate) fn srgb_component(linear: f32) -> u8 {
+    let linear = linear.clamp(0.0, 1.0);
+    let srgb = if linear <= 0.003_130_8 {
+        12.92 * linear
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb * 255.0).round() as u8
+}
+
+/// Formats a number with at most 3 decimal places and no trailing zeros.
+fn number(value: f32) -> String {
+    let formatted = format!("{:.3}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" => "0".to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::color::LinSrgba;
+    use nannou::glam::vec2;
+
+    use super::{number, SvgDocument, SvgStyle};
+    use crate::{
+        ColoredMesh, FillRule, Gradient, PathBuilder, ShapeBuilder, ShapeBuilderFactory,
+        ShapeError, ShapeSink,
+    };
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(-2.5), "-2.5");
+        assert_eq!(number(0.12345), "0.123");
+        assert_eq!(number(-0.0001), "0");
+    }
+
+    #[test]
+    fn test_path_data() -> Result<(), ShapeError> {
+        let path = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .quadratic_to(vec2(15.0, 5.0), vec2(10.0, 10.0))?
+            .cubic_to(vec2(7.5, 12.0), vec2(2.5, 12.0), vec2(0.0, 10.0))?
+            .close()
+            .build();
+        assert_eq!(
+            path.to_svg_path_data(),
+            "M0,0 L10,0 Q15,5 10,10 C7.5,12 2.5,12 0,10 Z"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_high_degree_curve_is_flattened() -> Result<(), ShapeError> {
+        let path = PathBuilder::new(vec2(0.0, 0.0))?
+            .add_bezier_curve(vec![
+                vec2(1.0, 0.0),
+                vec2(2.0, 0.0),
+                vec2(3.0, 0.0),
+                vec2(4.0, 0.0),
+            ])?
+            .build();
+        assert_eq!(path.to_svg_path_data(), "M0,0 L4,0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_document() -> Result<(), ShapeError> {
+        let petal = PathBuilder::new(vec2(0.0, 0.0))?
+            .quadratic_to(vec2(20.0, 30.0), vec2(0.0, 60.0))?
+            .quadratic_to(vec2(-20.0, 30.0), vec2(0.0, 0.0))?
+            .close()
+            .build();
+        let mut document = SvgDocument::new(200.0, 100.0);
+        document.add_path(
+            &petal,
+            SvgStyle::fill(LinSrgba::new(1.0, 0.0, 0.0, 0.5))
+                .with_stroke(LinSrgba::new(0.0, 0.0, 0.0, 1.0), 2.0),
+        );
+        let svg = document.to_string();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"100\" viewBox=\"-100 -50 200 100\">"));
+        assert!(svg.contains("<path d=\"M0,0 Q20,30 0,60 Q-20,30 0,0 Z\" fill=\"#ff0000\" fill-opacity=\"0.5\" stroke=\"#000000\" stroke-width=\"2\"/>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        let ring = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(4.0, 0.0))?
+            .line_to(vec2(0.0, 4.0))?
+            .close()
+            .move_to(vec2(1.0, 1.0))?
+            .line_to(vec2(2.0, 1.0))?
+            .line_to(vec2(1.0, 2.0))?
+            .close()
+            .build();
+        document.add_path(
+            &ring,
+            SvgStyle::fill(LinSrgba::new(0.0, 0.0, 1.0, 1.0)).with_fill_rule(FillRule::EvenOdd),
+        );
+        assert!(document.to_string().contains("<path d=\"M0,0 L4,0 L0,4 Z M1,1 L2,1 L1,2 Z\" fill=\"#0000ff\" fill-rule=\"evenodd\" stroke=\"none\"/>"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_gradient_fill() -> Result<(), ShapeError> {
+        let mut document = SvgDocument::new(100.0, 100.0);
+        let gradient = Gradient::linear(vec2(0.0, 0.0), vec2(10.0, 0.0))
+            .with_stop(0.0, LinSrgba::new(0.0, 0.0, 0.0, 1.0))
+            .with_stop(1.0, LinSrgba::new(1.0, 1.0, 1.0, 0.5));
+        document
+            .start_shape(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .line_to(vec2(0.0, 10.0))?
+            .close()
+            .fill_gradient(&gradient);
+        document
+            .start_shape(vec2(0.0, 0.0))?
+            .line_to(vec2(5.0, 0.0))?
+            .line_to(vec2(0.0, 5.0))?
+            .close()
+            .fill_gradient(
+                &Gradient::radial(vec2(1.0, 2.0), 3.0)
+                    .with_stop(0.5, LinSrgba::new(1.0, 0.0, 0.0, 1.0)),
+            );
+        let svg = document.to_string();
+        assert!(svg.contains("<defs>\n<linearGradient id=\"gradient0\" x1=\"0\" y1=\"0\" x2=\"10\" y2=\"0\" gradientUnits=\"userSpaceOnUse\" color-interpolation=\"linearRGB\"><stop offset=\"0\" stop-color=\"#000000\"/><stop offset=\"1\" stop-color=\"#ffffff\" stop-opacity=\"0.5\"/></linearGradient>\n"));
+        assert!(svg.contains("<radialGradient id=\"gradient1\" cx=\"1\" cy=\"2\" r=\"3\" gradientUnits=\"userSpaceOnUse\" color-interpolation=\"linearRGB\"><stop offset=\"0.5\" stop-color=\"#ff0000\"/></radialGradient>\n</defs>"));
+        // one path per fill, not one per triangle
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains(
+            "<path d=\"M0,0 L10,0 L10,10 L0,10 Z\" fill=\"url(#gradient0)\" stroke=\"none\"/>"
+        ));
+        assert!(
+            svg.contains("<path d=\"M0,0 L5,0 L0,5 Z\" fill=\"url(#gradient1)\" stroke=\"none\"/>")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mesh_fill() {
+        let red = LinSrgba::new(1.0, 0.0, 0.0, 1.0);
+        let mesh = ColoredMesh {
+            vertices: vec![
+                (vec2(0.0, 0.0), red),
+                (vec2(1.0, 0.0), red),
+                (vec2(0.0, 1.0), red),
+                (vec2(1.0, 1.0), red),
+            ],
+            indices: vec![0, 1, 2, 1, 3, 2],
+        };
+        let mut document = SvgDocument::new(10.0, 10.0);
+        document.fill_mesh(&mesh);
+        let svg = document.to_string();
+        assert!(!svg.contains("<defs>"));
+        assert!(svg.contains("<path d=\"M0,0 L1,0 L0,1 Z\" fill=\"#ff0000\" stroke=\"none\"/>"));
+        assert!(svg.contains("<path d=\"M1,0 L1,1 L0,1 Z\" fill=\"#ff0000\" stroke=\"none\"/>"));
+    }
+
+    #[test]
+    fn test_background() {
+        let document =
+            SvgDocument::new(10.0, 10.0).with_background(LinSrgba::new(1.0, 1.0, 1.0, 1.0));
+        assert!(document
+            .to_string()
+            .contains("<rect x=\"-5\" y=\"-5\" width=\"10\" height=\"10\" fill=\"#ffffff\"/>"));
+    }
+}
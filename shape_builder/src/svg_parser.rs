@@ -0,0 +1,540 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use nannou::glam::{vec2, Vec2};
+
+use crate::{ShapeBuilder, ShapeError};
+
+/// Reasons why SVG path data could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgPathError {
+    /// Path data does not contain any command.
+    Empty,
+    /// Path data has to start with a `M` or `m` command.
+    MissingMoveTo { position: usize },
+    /// Character which is neither a command nor a part of a number.
+    UnexpectedCharacter { position: usize, character: char },
+    /// Number or flag expected at the given position.
+    ExpectedNumber { position: usize },
+}
+
+impl Display for SvgPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgPathError::Empty => write!(f, "path data is empty"),
+            SvgPathError::MissingMoveTo { position } => {
+                write!(
+                    f,
+                    "path data has to start with a move command (at {})",
+                    position
+                )
+            }
+            SvgPathError::UnexpectedCharacter {
+                position,
+                character,
+            } => write!(f, "unexpected character '{}' at {}", character, position),
+            SvgPathError::ExpectedNumber { position } => {
+                write!(f, "expected a number at {}", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SvgPathError {}
+
+/// Path command with absolute coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SvgCommand {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadraticTo {
+        ctrl: Vec2,
+        to: Vec2,
+    },
+    CubicTo {
+        ctrl1: Vec2,
+        ctrl2: Vec2,
+        to: Vec2,
+    },
+    /// Elliptical arc, `x_axis_rotation` is converted to radians.
+    ArcTo {
+        radii: Vec2,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vec2,
+    },
+    Close,
+}
+
+/// Parsed SVG path data (the `d` attribute of `<path>`).
+///
+/// Coordinates are taken as they are, so shapes drawn in vector editors
+/// (with the y axis pointing down) come out upside down in nannou.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgPath {
+    commands: Vec<SvgCommand>,
+}
+
+impl SvgPath {
+    pub fn parse(data: &str) -> Result<Self, SvgPathError> {
+        Parser::new(data).parse()
+    }
+
+    pub fn commands(&self) -> &[SvgCommand] {
+        &self.commands
+    }
+
+    /// Point where the first subpath starts, a natural start for the builder passed to [SvgPath::replay].
+    pub fn start(&self) -> Vec2 {
+        match self.commands[0] {
+            SvgCommand::MoveTo(start) => start,
+            _ => unreachable!("parsed path always starts with a move"),
+        }
+    }
+
+    /// Adds all the commands of the path to `builder`, every move starting a new subpath.
+    ///
+    /// SVG allows curves of zero length, they are skipped as they draw nothing.
+    pub fn replay<B: ShapeBuilder<Vec2>>(&self, builder: B) -> Result<B, ShapeError> {
+        self.commands.iter().try_fold(builder, |builder, command| {
+            if command.has_no_length(builder.current_point()) {
+                return Ok(builder);
+            }
+            match *command {
+                SvgCommand::MoveTo(to) => builder.move_to(to),
+                SvgCommand::LineTo(to) => builder.line_to(to),
+                SvgCommand::QuadraticTo { ctrl, to } => builder.quadratic_to(ctrl, to),
+                SvgCommand::CubicTo { ctrl1, ctrl2, to } => builder.cubic_to(ctrl1, ctrl2, to),
+                SvgCommand::ArcTo {
+                    radii,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    to,
+                } => builder.arc_to(radii, x_axis_rotation, large_arc, sweep, to),
+                SvgCommand::Close => Ok(builder.close()),
+            }
+        })
+    }
+}
+
+impl SvgCommand {
+    /// Whether the command draws a curve staying on `from`.
+    fn has_no_length(&self, from: Vec2) -> bool {
+        match *self {
+            SvgCommand::MoveTo(_) | SvgCommand::Close => false,
+            SvgCommand::LineTo(to) | SvgCommand::ArcTo { to, .. } => to == from,
+            SvgCommand::QuadraticTo { ctrl, to } => ctrl == from && to == from,
+            SvgCommand::CubicTo { ctrl1, ctrl2, to } => {
+                ctrl1 == from && ctrl2 == from && to == from
+            }
+        }
+    }
+}
+
+impl FromStr for SvgPath {
+    type Err = SvgPathError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        SvgPath::parse(data)
+    }
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    position: usize,
+    commands: Vec<SvgCommand>,
+    current: Vec2,
+    subpath_start: Vec2,
+    /// Control point to reflect for the `S` and `T` commands.
+    last_ctrl: Option<(u8, Vec2)>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(data: &'a str) -> Self {
+        Parser {
+            data: data.as_bytes(),
+            position: 0,
+            commands: vec![],
+            current: Vec2::ZERO,
+            subpath_start: Vec2::ZERO,
+            last_ctrl: None,
+        }
+    }
+
+    fn parse(mut self) -> Result<SvgPath, SvgPathError> {
+        self.skip_separators();
+        if self.position == self.data.len() {
+            return Err(SvgPathError::Empty);
+        }
+        let mut command = None;
+        while self.position < self.data.len() {
+            let c = self.data[self.position];
+            if c.is_ascii_alphabetic() {
+                if command.is_none() && !c.eq_ignore_ascii_case(&b'M') {
+                    return Err(SvgPathError::MissingMoveTo {
+                        position: self.position,
+                    });
+                }
+                self.position += 1;
+                command = Some(c);
+            } else if matches!(command, None | Some(b'Z') | Some(b'z')) {
+                return Err(self.unexpected_character());
+            }
+            let c = command.unwrap();
+            self.parse_command(c)?;
+            // coordinates following a move are implicit lines
+            command = match c {
+                b'M' => Some(b'L'),
+                b'm' => Some(b'l'),
+                c => Some(c),
+            };
+            self.skip_separators();
+        }
+        Ok(SvgPath {
+            commands: self.commands,
+        })
+    }
+
+    fn parse_command(&mut self, command: u8) -> Result<(), SvgPathError> {
+        let relative = command.is_ascii_lowercase();
+        let origin = if relative { self.current } else { Vec2::ZERO };
+        let svg_command = match command.to_ascii_uppercase() {
+            b'M' => {
+                let to = origin + self.point()?;
+                self.subpath_start = to;
+                SvgCommand::MoveTo(to)
+            }
+            b'L' => SvgCommand::LineTo(origin + self.point()?),
+            b'H' => SvgCommand::LineTo(vec2(origin.x + self.number()?, self.current.y)),
+            b'V' => SvgCommand::LineTo(vec2(self.current.x, origin.y + self.number()?)),
+            b'Q' => SvgCommand::QuadraticTo {
+                ctrl: origin + self.point()?,
+                to: origin + self.point()?,
+            },
+            b'T' => SvgCommand::QuadraticTo {
+                ctrl: self.reflected_ctrl(b'Q'),
+                to: origin + self.point()?,
+            },
+            b'C' => SvgCommand::CubicTo {
+                ctrl1: origin + self.point()?,
+                ctrl2: origin + self.point()?,
+                to: origin + self.point()?,
+            },
+            b'S' => SvgCommand::CubicTo {
+                ctrl1: self.reflected_ctrl(b'C'),
+                ctrl2: origin + self.point()?,
+                to: origin + self.point()?,
+            },
+            b'A' => SvgCommand::ArcTo {
+                radii: self.point()?,
+                x_axis_rotation: self.number()?.to_radians(),
+                large_arc: self.flag()?,
+                sweep: self.flag()?,
+                to: origin + self.point()?,
+            },
+            b'Z' => SvgCommand::Close,
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected_character());
+            }
+        };
+        self.last_ctrl = None;
+        match svg_command {
+            SvgCommand::MoveTo(to) | SvgCommand::LineTo(to) | SvgCommand::ArcTo { to, .. } => {
+                self.current = to
+            }
+            SvgCommand::QuadraticTo { ctrl, to } => {
+                self.last_ctrl = Some((b'Q', ctrl));
+                self.current = to;
+            }
+            SvgCommand::CubicTo { ctrl2, to, .. } => {
+                self.last_ctrl = Some((b'C', ctrl2));
+                self.current = to;
+            }
+            SvgCommand::Close => self.current = self.subpath_start,
+        }
+        self.commands.push(svg_command);
+        Ok(())
+    }
+
+    fn reflected_ctrl(&self, kind: u8) -> Vec2 {
+        match self.last_ctrl {
+            Some((k, ctrl)) if k == kind => self.current * 2.0 - ctrl,
+            _ => self.current,
+        }
+    }
+
+    fn point(&mut self) -> Result<Vec2, SvgPathError> {
+        Ok(vec2(self.number()?, self.number()?))
+    }
+
+    fn number(&mut self) -> Result<f32, SvgPathError> {
+        self.skip_separators();
+        let start = self.position;
+        let mut end = start;
+        if end < self.data.len() && matches!(self.data[end], b'+' | b'-') {
+            end += 1;
+        }
+        let mut digits = 0;
+        let mut dot = false;
+        while end < self.data.len() {
+            match self.data[end] {
+                b'0'..=b'9' => digits += 1,
+                b'.' if !dot => dot = true,
+                _ => break,
+            }
+            end += 1;
+        }
+        if digits == 0 {
+            return Err(SvgPathError::ExpectedNumber { position: start });
+        }
+        if end < self.data.len() && matches!(self.data[end], b'e' | b'E') {
+            let mut exponent_end = end + 1;
+            if exponent_end < self.data.len() && matches!(self.data[exponent_end], b'+' | b'-') {
+                exponent_end += 1;
+            }
+            let exponent_digits = self.data[exponent_end..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+            if exponent_digits > 0 {
+                end = exponent_end + exponent_digits;
+            }
+        }
+        self.position = end;
+        // only ASCII characters were consumed, so the slice is valid UTF-8
+        std::str::from_utf8(&self.data[start..end])
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .ok_or(SvgPathError::ExpectedNumber { position: start })
+    }
+
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_separators();
+        let flag = match self.data.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => {
+                return Err(SvgPathError::ExpectedNumber {
+                    position: self.position,
+                })
+            }
+        };
+        self.position += 1;
+        Ok(flag)
+    }
+
+    fn skip_separators(&mut self) {
+        while self.position < self.data.len()
+            && (self.data[self.position].is_ascii_whitespace() || self.data[self.position] == b',')
+        {
+            self.position += 1;
+        }
+    }
+
+    fn unexpected_character(&self) -> SvgPathError {
+        let character = std::str::from_utf8(&self.data[self.position..])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+        SvgPathError::UnexpectedCharacter {
+            position: self.position,
+            character,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use nannou::glam::vec2;
+
+    use super::{SvgCommand, SvgPath, SvgPathError};
+    use crate::{PathBuilder, ShapeBuilder, ShapeError};
+
+    #[test]
+    fn test_absolute_commands() {
+        let path = SvgPath::parse("M10 20 L30,40 H50 V60 Q1 2 3 4 C5 6 7 8 9 10 Z").unwrap();
+        assert_eq!(
+            path.commands(),
+            &[
+                SvgCommand::MoveTo(vec2(10.0, 20.0)),
+                SvgCommand::LineTo(vec2(30.0, 40.0)),
+                SvgCommand::LineTo(vec2(50.0, 40.0)),
+                SvgCommand::LineTo(vec2(50.0, 60.0)),
+                SvgCommand::QuadraticTo {
+                    ctrl: vec2(1.0, 2.0),
+                    to: vec2(3.0, 4.0)
+                },
+                SvgCommand::CubicTo {
+                    ctrl1: vec2(5.0, 6.0),
+                    ctrl2: vec2(7.0, 8.0),
+                    to: vec2(9.0, 10.0)
+                },
+                SvgCommand::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relative_commands() {
+        let path = SvgPath::parse("m10 10 l5 0 h5 v-5 q5 0 5 5 c0 5 -5 5 -5 5 z").unwrap();
+        assert_eq!(
+            path.commands(),
+            &[
+                SvgCommand::MoveTo(vec2(10.0, 10.0)),
+                SvgCommand::LineTo(vec2(15.0, 10.0)),
+                SvgCommand::LineTo(vec2(20.0, 10.0)),
+                SvgCommand::LineTo(vec2(20.0, 5.0)),
+                SvgCommand::QuadraticTo {
+                    ctrl: vec2(25.0, 5.0),
+                    to: vec2(25.0, 10.0)
+                },
+                SvgCommand::CubicTo {
+                    ctrl1: vec2(25.0, 15.0),
+                    ctrl2: vec2(20.0, 15.0),
+                    to: vec2(20.0, 15.0)
+                },
+                SvgCommand::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_implicit_commands_and_compact_numbers() {
+        let path = SvgPath::parse("M0,0 10,0-5.5.5 1e1-1E-1").unwrap();
+        assert_eq!(
+            path.commands(),
+            &[
+                SvgCommand::MoveTo(vec2(0.0, 0.0)),
+                SvgCommand::LineTo(vec2(10.0, 0.0)),
+                SvgCommand::LineTo(vec2(-5.5, 0.5)),
+                SvgCommand::LineTo(vec2(10.0, -0.1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_smooth_curves() {
+        let path =
+            SvgPath::parse("M0 0 Q10 10 20 0 T40 0 C50 10 60 10 70 0 S90 -10 100 0").unwrap();
+        assert_eq!(
+            path.commands()[2],
+            SvgCommand::QuadraticTo {
+                ctrl: vec2(30.0, -10.0),
+                to: vec2(40.0, 0.0)
+            }
+        );
+        assert_eq!(
+            path.commands()[4],
+            SvgCommand::CubicTo {
+                ctrl1: vec2(80.0, -10.0),
+                ctrl2: vec2(90.0, -10.0),
+                to: vec2(100.0, 0.0)
+            }
+        );
+        // without a previous curve the current point is the control point
+        let path = SvgPath::parse("M0 0 T10 0").unwrap();
+        assert_eq!(
+            path.commands()[1],
+            SvgCommand::QuadraticTo {
+                ctrl: vec2(0.0, 0.0),
+                to: vec2(10.0, 0.0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_arc() {
+        let path = SvgPath::parse("M0 0 a10 20 90 1020 0").unwrap();
+        assert_eq!(
+            path.commands()[1],
+            SvgCommand::ArcTo {
+                radii: vec2(10.0, 20.0),
+                x_axis_rotation: FRAC_PI_2,
+                large_arc: true,
+                sweep: false,
+                to: vec2(20.0, 0.0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(SvgPath::parse("  "), Err(SvgPathError::Empty));
+        assert_eq!(
+            SvgPath::parse("L 10 10"),
+            Err(SvgPathError::MissingMoveTo { position: 0 })
+        );
+        assert_eq!(
+            SvgPath::parse("M 10"),
+            Err(SvgPathError::ExpectedNumber { position: 4 })
+        );
+        assert_eq!(
+            SvgPath::parse("M 10 10 X 5"),
+            Err(SvgPathError::UnexpectedCharacter {
+                position: 8,
+                character: 'X'
+            })
+        );
+        assert_eq!(
+            SvgPath::parse("M 0 0 Z 5"),
+            Err(SvgPathError::UnexpectedCharacter {
+                position: 8,
+                character: '5'
+            })
+        );
+        assert_eq!(
+            SvgPath::parse("M 0 0 A 5 5 0 2 0 10 10"),
+            Err(SvgPathError::ExpectedNumber { position: 14 })
+        );
+    }
+
+    #[test]
+    fn test_replay_round_trip() -> Result<(), ShapeError> {
+        let data = "M0,0 L10,0 Q15,5 10,10 C7.5,12 2.5,12 0,10 Z";
+        let svg_path: SvgPath = data.parse().unwrap();
+        let path = svg_path
+            .replay(PathBuilder::new(svg_path.start())?)?
+            .build();
+        assert_eq!(path.to_svg_path_data(), data);
+        let expected = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .quadratic_to(vec2(15.0, 5.0), vec2(10.0, 10.0))?
+            .cubic_to(vec2(7.5, 12.0), vec2(2.5, 12.0), vec2(0.0, 10.0))?
+            .close()
+            .build();
+        assert_eq!(path, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_subpaths() -> Result<(), ShapeError> {
+        let data = "M0,0 L10,0 L10,10 Z M3,3 L7,3 L7,7 Z";
+        let svg_path: SvgPath = data.parse().unwrap();
+        let path = svg_path
+            .replay(PathBuilder::new(svg_path.start())?)?
+            .build();
+        assert_eq!(path.subpaths().len(), 2);
+        assert_eq!(path.subpaths()[1].start(), vec2(3.0, 3.0));
+        assert!(path.subpaths().iter().all(|s| s.is_closed()));
+        assert_eq!(path.to_svg_path_data(), data);
+        // a relative move after a close starts from the closed subpath's start
+        let svg_path = SvgPath::parse("M1,1 l4,0 l0,4 z m1,1 l1,0").unwrap();
+        assert_eq!(svg_path.commands()[4], SvgCommand::MoveTo(vec2(2.0, 2.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_skips_zero_length() -> Result<(), ShapeError> {
+        let svg_path = SvgPath::parse("M0,0 L0,0 L10,0 Q10,0 10,0 A5,5 0 0 1 10,0 Z").unwrap();
+        let path = svg_path
+            .replay(PathBuilder::new(svg_path.start())?)?
+            .build();
+        assert_eq!(path.to_svg_path_data(), "M0,0 L10,0 Z");
+        Ok(())
+    }
+}
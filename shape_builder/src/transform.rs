@@ -0,0 +1,151 @@
+use nannou::glam::{vec2, Affine2, Mat2, Vec2};
+
+use crate::BezierCurve;
+
+/// Affine transforms of shapes.
+///
+/// Bézier curves keep their shape under affine transforms, so only the
+/// control points get transformed and nothing is flattened.
+pub trait TransformShape: Sized {
+    /// Applies `transform` to every point of the shape.
+    fn transform(self, transform: Affine2) -> Self;
+
+    fn translate(self, offset: Vec2) -> Self {
+        self.transform(Affine2::from_translation(offset))
+    }
+
+    /// Rotates the shape counter-clockwise around the origin, `angle` is in radians.
+    fn rotate(self, angle: f32) -> Self {
+        self.transform(Affine2::from_angle(angle))
+    }
+
+    /// Scales the shape from the origin.
+    fn scale(self, factor: f32) -> Self {
+        self.scale_xy(Vec2::splat(factor))
+    }
+
+    fn scale_xy(self, factors: Vec2) -> Self {
+        self.transform(Affine2::from_scale(factors))
+    }
+
+    /// Slants the shape like the CSS `skew` function, angles are in radians.
+    ///
+    /// `x_angle` tilts vertical lines and `y_angle` tilts horizontal ones.
+    fn skew(self, x_angle: f32, y_angle: f32) -> Self {
+        self.transform(Affine2::from_mat2(Mat2::from_cols(
+            vec2(1.0, y_angle.tan()),
+            vec2(x_angle.tan(), 1.0),
+        )))
+    }
+}
+
+impl TransformShape for BezierCurve<Vec2> {
+    fn transform(self, transform: Affine2) -> Self {
+        BezierCurve::from_points(
+            self.points()
+                .iter()
+                .map(|p| transform.transform_point2(*p))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use nannou::glam::{vec2, Affine2, Vec2};
+
+    use super::TransformShape;
+    use crate::{Flattening, Path, PathBuilder, ShapeBuilder, ShapeError};
+
+    fn petal() -> Result<Path, ShapeError> {
+        Ok(PathBuilder::new(vec2(0.0, 0.0))?
+            .quadratic_to(vec2(5.0, 10.0), vec2(0.0, 20.0))?
+            .line_to(vec2(0.0, 0.0))?
+            .close()
+            .build())
+    }
+
+    fn assert_points_eq(a: &[Vec<Vec2>], b: &[Vec<Vec2>]) {
+        let a = a.iter().flatten().collect::<Vec<_>>();
+        let b = b.iter().flatten().collect::<Vec<_>>();
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.into_iter().zip(b) {
+            assert!(a.distance(*b) < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_translate_and_scale() -> Result<(), ShapeError> {
+        let path = petal()?.scale(2.0).translate(vec2(10.0, 0.0));
+        let segments = path.subpaths()[0].segments();
+        assert_eq!(path.subpaths()[0].start(), vec2(10.0, 0.0));
+        assert_eq!(segments[0].points()[1], vec2(20.0, 20.0));
+        assert_eq!(segments[0].end(), vec2(10.0, 40.0));
+        assert!(path.subpaths()[0].is_closed());
+        assert!((path.length() - petal()?.length() * 2.0).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotate() -> Result<(), ShapeError> {
+        let rotated = petal()?.rotate(FRAC_PI_2);
+        assert!(rotated.subpaths()[0].end().distance(Vec2::ZERO) < 1e-5);
+        let tip = rotated.subpaths()[0].segments()[0].end();
+        assert!(tip.distance(vec2(-20.0, 0.0)) < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_skew() -> Result<(), ShapeError> {
+        let square = PathBuilder::new(vec2(0.0, 0.0))?
+            .line_to(vec2(10.0, 0.0))?
+            .line_to(vec2(10.0, 10.0))?
+            .close()
+            .build()
+            .skew(FRAC_PI_4, 0.0);
+        let points = square.flatten(Flattening::default());
+        assert_points_eq(
+            &points,
+            &[vec![
+                vec2(0.0, 0.0),
+                vec2(10.0, 0.0),
+                vec2(20.0, 10.0),
+                vec2(0.0, 0.0),
+            ]],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_transform_matches_flattened_points() -> Result<(), ShapeError> {
+        let transform = Affine2::from_scale_angle_translation(vec2(2.0, 0.5), 0.3, vec2(1.0, 2.0));
+        let flattening = Flattening::Divisions(5);
+        let transformed_points = petal()?
+            .flatten(flattening)
+            .iter()
+            .map(|s| s.iter().map(|p| transform.transform_point2(*p)).collect())
+            .collect::<Vec<_>>();
+        assert_points_eq(
+            &petal()?.transform(transform).flatten(flattening),
+            &transformed_points,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_transforms_points_so_far() -> Result<(), ShapeError> {
+        let path = PathBuilder::new(vec2(1.0, 0.0))?
+            .line_to(vec2(2.0, 0.0))?
+            .translate(vec2(0.0, 5.0))
+            .line_to(vec2(2.0, 0.0))?
+            .build();
+        let points = path.flatten(Flattening::default());
+        assert_eq!(
+            points,
+            vec![vec![vec2(1.0, 5.0), vec2(2.0, 5.0), vec2(2.0, 0.0)]]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use nannou::image::codecs::gif::{GifEncoder, Repeat};
+use nannou::image::error::{DecodingError, ImageFormatHint, ParameterError, ParameterErrorKind};
+use nannou::image::{self, Delay, ImageError, ImageFormat, ImageResult};
+
+/// Animation assembled from the PNG frames of a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Animation {
+    Gif,
+    /// Animated PNG, without the color loss of a GIF.
+    Apng,
+}
+
+impl Animation {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Animation::Gif => "gif",
+            Animation::Apng => "apng",
+        }
+    }
+
+    /// Assembles the PNG `frames` into an animation `file`, looping at `fps`
+    /// frames per second.
+    pub fn assemble(self, frames: &[PathBuf], file: &Path, fps: u32) -> ImageResult<()> {
+        match self {
+            Animation::Gif => assemble_gif(frames, file, fps),
+            Animation::Apng => assemble_apng(frames, file, fps),
+        }
+    }
+}
+
+impl FromStr for Animation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gif" => Ok(Animation::Gif),
+            "apng" => Ok(Animation::Apng),
+            _ => Err(format!("unknown animation `{}`, either gif or apng", s)),
+        }
+    }
+}
+
+fn assemble_gif(frames: &[PathBuf], file: &Path, fps: u32) -> ImageResult<()> {
+    let mut encoder = GifEncoder::new(BufWriter::new(File::create(file)?));
+    encoder.set_repeat(Repeat::Infinite)?;
+    for frame in frames {
+        let buffer = image::open(frame)?.to_rgba8();
+        let delay = Delay::from_numer_denom_ms(1000, fps.max(1));
+        encoder.encode_frame(image::Frame::from_parts(buffer, 0, 0, delay))?;
+    }
+    Ok(())
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Rewraps the already compressed image data of each PNG frame into the
+/// frames of an animated PNG, so that nothing is decoded or encoded again.
+///
+/// All the frames must have the same header, i.e. size and color type, and
+/// the chunks of the first one other than the image data are kept.
+fn assemble_apng(frames: &[PathBuf], file: &Path, fps: u32) -> ImageResult<()> {
+    let mut apng = PNG_SIGNATURE.to_vec();
+    let mut sequence = 0u32;
+    let mut first_header = None;
+    for (index, frame) in frames.iter().enumerate() {
+        let png = std::fs::read(frame)?;
+        let chunks = png_chunks(&png).ok_or_else(|| {
+            ImageError::Decoding(DecodingError::new(
+                ImageFormatHint::PathExtension(frame.clone()),
+                format!("{} is not a PNG image", frame.display()),
+            ))
+        })?;
+        let header = chunks
+            .iter()
+            .find(|(kind, _)| kind == b"IHDR")
+            .map(|(_, data)| *data)
+            .filter(|header| header.len() >= 8)
+            .ok_or_else(|| {
+                ImageError::Decoding(DecodingError::new(
+                    ImageFormatHint::Exact(ImageFormat::Png),
+                    format!("{} has no header", frame.display()),
+                ))
+            })?;
+        match &first_header {
+            None => {
+                write_chunk(&mut apng, b"IHDR", header);
+                let mut control = (frames.len() as u32).to_be_bytes().to_vec();
+                // plays forever
+                control.extend(0u32.to_be_bytes());
+                write_chunk(&mut apng, b"acTL", &control);
+                for (kind, data) in &chunks {
+                    if !matches!(
+                        kind,
+                        b"IHDR" | b"IDAT" | b"IEND" | b"acTL" | b"fcTL" | b"fdAT"
+                    ) {
+                        write_chunk(&mut apng, kind, data);
+                    }
+                }
+                first_header = Some(header.to_vec());
+            }
+            Some(first_header) if first_header != header => {
+                return Err(ImageError::Parameter(ParameterError::from_kind(
+                    ParameterErrorKind::DimensionMismatch,
+                )));
+            }
+            Some(_) => {}
+        }
+
+        let mut control = sequence.to_be_bytes().to_vec();
+        sequence += 1;
+        // same size as the header, at the top left corner
+        control.extend(&header[..8]);
+        control.extend([0; 8]);
+        control.extend(1u16.to_be_bytes());
+        control.extend(u16::try_from(fps).unwrap_or(u16::MAX).to_be_bytes());
+        // no disposal, and the frame replaces the previous one
+        control.extend([0, 0]);
+        write_chunk(&mut apng, b"fcTL", &control);
+
+        for (_, data) in chunks.iter().filter(|(kind, _)| kind == b"IDAT") {
+            if index == 0 {
+                write_chunk(&mut apng, b"IDAT", data);
+            } else {
+                let mut frame_data = sequence.to_be_bytes().to_vec();
+                sequence += 1;
+                frame_data.extend(*data);
+                write_chunk(&mut apng, b"fdAT", &frame_data);
+            }
+        }
+    }
+    write_chunk(&mut apng, b"IEND", &[]);
+    std::fs::write(file, apng)?;
+    Ok(())
+}
+
+/// Kinds and data of the chunks of a PNG file, `None` if it isn't one.
+fn png_chunks(png: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let mut rest = png.strip_prefix(PNG_SIGNATURE)?;
+    let mut chunks = Vec::new();
+    while !rest.is_empty() {
+        let length = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+        let kind = rest.get(4..8)?.try_into().ok()?;
+        let data = rest.get(8..8 + length)?;
+        chunks.push((kind, data));
+        // skips the CRC too
+        rest = rest.get(12 + length..)?;
+    }
+    Some(chunks)
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// CRC of the PNG chunks, over their kind and data.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use nannou::image::{self, AnimationDecoder, Rgba, RgbaImage};
+
+    use crate::animation::{crc32, png_chunks, Animation};
+    use crate::frame_file;
+
+    /// Frames of a pixel going from black to white.
+    fn frames(dir: &str) -> Vec<PathBuf> {
+        let dir = std::env::temp_dir().join(dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        (0..3)
+            .map(|frame| {
+                let file = frame_file(&dir, frame);
+                let gray = frame as u8 * 127;
+                RgbaImage::from_pixel(2, 2, Rgba([gray, gray, gray, 255]))
+                    .save(&file)
+                    .unwrap();
+                file
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn test_apng() {
+        let frames = frames("sketch_runtime_test_apng");
+        let file = std::env::temp_dir().join("sketch_runtime_test.apng");
+        Animation::Apng.assemble(&frames, &file, 30).unwrap();
+
+        let apng = std::fs::read(&file).unwrap();
+        let kinds = png_chunks(&apng)
+            .unwrap()
+            .into_iter()
+            .map(|(kind, _)| String::from_utf8(kind.to_vec()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds[..4], ["IHDR", "acTL", "fcTL", "IDAT"]);
+        assert_eq!(kinds.iter().filter(|k| *k == "fcTL").count(), 3);
+        assert_eq!(kinds.last().unwrap(), "IEND");
+        // still a valid PNG, showing the first frame
+        let first = image::load_from_memory(&apng).unwrap().to_rgba8();
+        assert_eq!(first.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_gif() {
+        let frames = frames("sketch_runtime_test_gif");
+        let file = std::env::temp_dir().join("sketch_runtime_test.gif");
+        Animation::Gif.assemble(&frames, &file, 30).unwrap();
+
+        let gif = image::codecs::gif::GifDecoder::new(std::fs::File::open(&file).unwrap())
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(gif.len(), 3);
+        assert_eq!(gif[2].buffer().get_pixel(0, 0), &Rgba([254, 254, 254, 255]));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("gif".parse(), Ok(Animation::Gif));
+        assert_eq!("apng".parse(), Ok(Animation::Apng));
+        assert!("mp4".parse::<Animation>().is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use nannou::glam::{vec2, Vec2};
+use nannou::image::{self, ImageResult, RgbaImage};
+use shape_builder::{Rasterizer, ShapeSink};
+
+use crate::{Random, Sketch};
+
+/// Environment variable which makes [check_golden] write the golden images
+/// instead of comparing with them.
+pub const UPDATE_GOLDEN: &str = "UPDATE_GOLDEN";
+
+/// A sketch which can also be run without a window, drawing on any
+/// [ShapeSink], e.g. the [Rasterizer] of [HeadlessRun].
+///
+/// The [Sketch] implementation usually calls into these, so that both draw the
+/// same.
+pub trait Headless: Sketch {
+    /// Creates the initial model for a canvas of `size`, like [Sketch::model]
+    /// does for the window.
+    fn canvas_model(size: Vec2, random: &mut Random) -> Self;
+
+    /// Advances the model before drawing `frame`, like [Sketch::update].
+    fn step(&mut self, frame: u64, random: &mut Random);
+
+    /// Draws `frame` of the model, with the origin in the middle of the canvas.
+    fn draw(&self, frame: u64, sink: &mut dyn ShapeSink);
+}
+
+/// Sketch running on the CPU, frame after frame, without a window or a GPU.
+pub struct HeadlessRun<S> {
+    sketch: S,
+    random: Random,
+    canvas: Rasterizer,
+    frame: u64,
+}
+
+impl<S: Headless> HeadlessRun<S> {
+    /// Sketch on a canvas of `width` by `height` pixels, seeded with `seed`.
+    pub fn new(width: u32, height: u32, seed: u64) -> Self {
+        let mut random = Random::new(seed);
+        let size = vec2(width as f32, height as f32);
+        HeadlessRun {
+            sketch: S::canvas_model(size, &mut random),
+            random,
+            canvas: Rasterizer::new(width, height),
+            frame: 0,
+        }
+    }
+
+    /// Updates and draws the next frame. Like in a window, the frame is drawn
+    /// over the previous one.
+    pub fn next_frame(&mut self) -> &Rasterizer {
+        self.sketch.step(self.frame, &mut self.random);
+        self.sketch.draw(self.frame, &mut self.canvas);
+        self.frame += 1;
+        &self.canvas
+    }
+
+    /// Number of frames drawn so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn canvas(&self) -> &Rasterizer {
+        &self.canvas
+    }
+}
+
+/// File of a frame in a directory of numbered frames, e.g. `0042.png`.
+pub fn frame_file(dir: &Path, frame: u64) -> PathBuf {
+    dir.join(format!("{:04}.png", frame))
+}
+
+/// Draws `frames` frames of the sketch on a canvas of `width` by `height`
+/// pixels, and saves each one as a numbered PNG in `dir`.
+///
+/// Returns the saved files, the last one makes a thumbnail of the sketch.
+pub fn render<S: Headless>(
+    dir: &Path,
+    width: u32,
+    height: u32,
+    frames: u64,
+    seed: u64,
+) -> ImageResult<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    let mut run = HeadlessRun::<S>::new(width, height, seed);
+    (0..frames)
+        .map(|frame| {
+            let file = frame_file(dir, frame);
+            run.next_frame().save(&file)?;
+            Ok(file)
+        })
+        .collect()
+}
+
+/// Compares `image` with the golden image in `file`, allowing small color
+/// differences so that floating point noise doesn't fail the comparison.
+///
+/// With the [UPDATE_GOLDEN] environment variable set, saves `image` as the
+/// new golden image instead.
+pub fn check_golden(image: &RgbaImage, file: impl AsRef<Path>) -> Result<(), String> {
+    let file = file.as_ref();
+    if std::env::var_os(UPDATE_GOLDEN).is_some() {
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        }
+        return image
+            .save(file)
+            .map_err(|e| format!("cannot save {}: {}", file.display(), e));
+    }
+    let golden = image::open(file)
+        .map_err(|e| format!("cannot open {}: {}", file.display(), e))?
+        .to_rgba8();
+    if golden.dimensions() != image.dimensions() {
+        return Err(format!(
+            "{:?} image, golden {} is {:?}",
+            image.dimensions(),
+            file.display(),
+            golden.dimensions()
+        ));
+    }
+    let different = image
+        .pixels()
+        .zip(golden.pixels())
+        .filter(|(a, b)| a.0.iter().zip(b.0.iter()).any(|(a, b)| a.abs_diff(*b) > 2))
+        .count();
+    // a few pixels on the edges of shapes may flip
+    if different * 1000 > golden.pixels().len() {
+        return Err(format!(
+            "{} pixels differ from {}, run with {}=1 to accept the new image",
+            different,
+            file.display(),
+            UPDATE_GOLDEN
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use nannou::color::lin_srgba;
+    use nannou::glam::{vec2, Vec2};
+    use shape_builder::{ShapeBuilder, ShapeBuilderFactory, ShapeSink};
+
+    use crate::headless::{check_golden, Headless, HeadlessRun};
+    use crate::{Random, Sketch};
+
+    /// Square moving right by a pixel per frame, at a random height.
+    struct Square {
+        position: Vec2,
+    }
+
+    impl Sketch for Square {
+        const TITLE: &'static str = "square";
+
+        fn model(app: &nannou::App, random: &mut Random) -> Self {
+            Self::canvas_model(app.window_rect().wh(), random)
+        }
+
+        fn view(&self, _app: &nannou::App, _frame: nannou::Frame) {}
+    }
+
+    impl Headless for Square {
+        fn canvas_model(size: Vec2, random: &mut Random) -> Self {
+            Square {
+                position: vec2(-size.x * 0.5, random.range(-2.0f32, 2.0).round()),
+            }
+        }
+
+        fn step(&mut self, _frame: u64, _random: &mut Random) {
+            self.position.x += 1.0;
+        }
+
+        fn draw(&self, _frame: u64, sink: &mut dyn ShapeSink) {
+            sink.clear(lin_srgba(1.0, 1.0, 1.0, 1.0));
+            let p = self.position;
+            if let Ok(square) = sink
+                .start_shape(p)
+                .and_then(|b| b.line_to(p + vec2(2.0, 0.0)))
+                .and_then(|b| b.line_to(p + vec2(2.0, 2.0)))
+                .and_then(|b| b.line_to(p + vec2(0.0, 2.0)))
+            {
+                square.close().fill(lin_srgba(0.0, 0.0, 0.0, 1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_headless_run() {
+        let mut run = HeadlessRun::<Square>::new(8, 8, 1);
+        let same = HeadlessRun::<Square>::new(8, 8, 1).sketch.position;
+        assert_eq!(run.sketch.position, same);
+        run.next_frame();
+        run.next_frame();
+        assert_eq!(run.frame(), 2);
+        // the square moved from the left edge by two pixels
+        let y = (4.0 - same.y - 1.0) as u32;
+        assert_eq!(run.canvas().pixel(2, y), lin_srgba(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(run.canvas().pixel(1, y), lin_srgba(1.0, 1.0, 1.0, 1.0));
+        assert_eq!(run.canvas().pixel(4, y), lin_srgba(1.0, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_check_golden() {
+        let file = std::env::temp_dir().join("sketch_runtime_test_check_golden.png");
+        let mut run = HeadlessRun::<Square>::new(8, 8, 1);
+        let image = run.next_frame().to_image();
+        image.save(&file).unwrap();
+        assert_eq!(check_golden(&image, &file), Ok(()));
+        let moved = run.next_frame().to_image();
+        assert!(check_golden(&moved, &file).is_err());
+        let _ = std::fs::remove_file(file);
+    }
+}
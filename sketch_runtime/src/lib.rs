@@ -0,0 +1,232 @@
+//! Window setup and entry points shared by the sketches, which then only
+//! implement [Sketch].
+//!
+//! ```ignore
+//! // main.rs, native app entry point
+//! fn main() {
+//!     sketch_runtime::run::<sketch::Model>();
+//! }
+//!
+//! // lib.rs, web app entry point
+//! #[wasm_bindgen]
+//! pub async fn main_web() {
+//!     sketch_runtime::run::<sketch::Model>();
+//! }
+//! ```
+//!
+//! All the randomness of a sketch comes from the [Random] it is given, seeded
+//! with `--seed <seed>` natively or `?seed=<seed>` in the page URL.
+//!
+//! A sketch which also implements [Headless] can be drawn without a window,
+//! on the CPU, into PNG frames, e.g. for thumbnails and golden image tests.
+//!
+//! Natively, any sketch is recorded to PNG frames while [RECORD_KEY] is
+//! toggled on, or from the start with `--record`. `--record=gif` and
+//! `--record=apng` also assemble an [Animation] of each recording.
+
+use std::num::ParseIntError;
+
+use async_std::task::block_on;
+use nannou::prelude::*;
+use nannou::wgpu::{Backends, DeviceDescriptor, Limits};
+use nannou::window;
+
+pub use animation::Animation;
+pub use headless::{check_golden, frame_file, render, Headless, HeadlessRun, UPDATE_GOLDEN};
+pub use random::{seed_from_args, seed_from_query, Random};
+pub use record::{record_options_from_args, RecordOptions, Recorder, RECORDINGS};
+
+mod animation;
+mod headless;
+mod random;
+mod record;
+
+/// Key starting and stopping the recording of the sketch.
+pub const RECORD_KEY: Key = Key::R;
+
+/// A sketch is its own model, nannou calls back into it for updates, drawing
+/// and window events.
+pub trait Sketch: Sized + 'static {
+    /// Title of the sketch window.
+    const TITLE: &'static str;
+
+    /// Creates the initial model, once the window is open.
+    fn model(app: &App, random: &mut Random) -> Self;
+
+    /// Model update, called before each frame.
+    fn update(&mut self, _app: &App, _random: &mut Random, _update: Update) {}
+
+    fn view(&self, app: &App, frame: Frame);
+
+    /// Event handler
+    fn event(&mut self, _app: &App, _random: &mut Random, _event: WindowEvent) {}
+}
+
+/// Model given to nannou, the sketch along with its random numbers and
+/// recordings.
+struct Running<S> {
+    sketch: S,
+    random: Random,
+    window: window::Id,
+    recorder: Recorder,
+}
+
+/// Opens the window of the sketch and runs it until the app exits, seeded with
+/// [seed] and recorded with [record_options].
+///
+/// Works both natively and in the browser, where it also logs panics to the
+/// console in debug builds. Invalid options are logged and nothing runs.
+pub fn run<S: Sketch>() {
+    #[cfg(all(target_arch = "wasm32", debug_assertions))]
+    console_error_panic_hook::set_once();
+    let seed = match seed() {
+        Ok(seed) => seed,
+        Err(e) => return log(&format!("{}: invalid seed, {}", S::TITLE, e)),
+    };
+    match record_options() {
+        Ok(options) => run_seeded::<S>(seed, options),
+        Err(message) => log(&format!("{}: {}", S::TITLE, message)),
+    }
+}
+
+/// Same as [run] with the given seed and recording.
+pub fn run_seeded<S: Sketch>(seed: u64, options: RecordOptions) {
+    log(&format!("{} seed {}", S::TITLE, seed));
+    block_on(async {
+        run_app::<S>(seed, options).await;
+    });
+}
+
+/// Seed asked for on the command line natively, or in the page URL on the web,
+/// a new random seed otherwise.
+pub fn seed() -> Result<u64, ParseIntError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let seed = seed_from_args(std::env::args());
+    #[cfg(target_arch = "wasm32")]
+    let seed = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .map_or(Ok(None), |query| seed_from_query(&query));
+    seed.map(|seed| seed.unwrap_or_else(nannou::rand::random))
+}
+
+/// Recording asked for on the command line natively, none on the web.
+pub fn record_options() -> Result<RecordOptions, String> {
+    if cfg!(target_arch = "wasm32") {
+        Ok(RecordOptions::default())
+    } else {
+        record_options_from_args(std::env::args())
+    }
+}
+
+fn log(message: &str) {
+    #[cfg(not(target_arch = "wasm32"))]
+    println!("{}", message);
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::log_1(&message.into());
+}
+
+async fn run_app<S: Sketch>(seed: u64, options: RecordOptions) {
+    app::Builder::new_async(move |app| {
+        Box::new(async move {
+            let window = create_window::<S>(app, seed).await;
+            let mut random = Random::new(seed);
+            let sketch = S::model(app, &mut random);
+            let mut recorder = Recorder::new(format!("{}-{}", S::TITLE, seed), options);
+            if options.start {
+                toggle_recording(app, window, &mut recorder);
+            }
+            Running {
+                sketch,
+                random,
+                window,
+                recorder,
+            }
+        })
+    })
+    .backends(Backends::PRIMARY | Backends::GL)
+    .update(update::<S>)
+    .exit(exit::<S>)
+    .run_async()
+    .await;
+}
+
+async fn create_window<S: Sketch>(app: &App, seed: u64) -> window::Id {
+    // webgl2 limits so that the same window opens in the browser
+    let device_desc = DeviceDescriptor {
+        limits: Limits {
+            max_texture_dimension_2d: 8192,
+            ..Limits::downlevel_webgl2_defaults()
+        },
+        ..Default::default()
+    };
+
+    app.new_window()
+        .device_descriptor(device_desc)
+        .title(format!("{} (seed {})", S::TITLE, seed))
+        .event(event::<S>)
+        .view(view::<S>)
+        .build_async()
+        .await
+        .unwrap()
+}
+
+fn update<S: Sketch>(app: &App, model: &mut Running<S>, update: Update) {
+    // while recording, each frame is saved and moves the sketch by the same
+    // timestep, however long drawing and saving it take
+    let (update, file) = model.recorder.next_frame(update);
+    if let (Some(file), Some(window)) = (file, app.window(model.window)) {
+        window.capture_frame(file);
+    }
+    model.sketch.update(app, &mut model.random, update);
+}
+
+fn view<S: Sketch>(app: &App, model: &Running<S>, frame: Frame) {
+    model.sketch.view(app, frame);
+}
+
+fn event<S: Sketch>(app: &App, model: &mut Running<S>, event: WindowEvent) {
+    #[cfg(not(target_arch = "wasm32"))]
+    if matches!(event, KeyPressed(key) if key == RECORD_KEY) {
+        toggle_recording(app, model.window, &mut model.recorder);
+    }
+    model.sketch.event(app, &mut model.random, event);
+}
+
+fn exit<S: Sketch>(app: &App, mut model: Running<S>) {
+    if model.recorder.is_recording() {
+        toggle_recording(app, model.window, &mut model.recorder);
+    }
+}
+
+/// Starts a new recording, or stops the current one once all its frames are
+/// saved, and assembles them into the animation if one was asked for.
+fn toggle_recording(app: &App, window: window::Id, recorder: &mut Recorder) {
+    if !recorder.is_recording() {
+        match recorder.start() {
+            Ok(dir) => log(&format!(
+                "recording to {}, press {:?} to stop",
+                dir.display(),
+                RECORD_KEY
+            )),
+            Err(e) => log(&format!("cannot record: {}", e)),
+        }
+        return;
+    }
+
+    let frames = recorder.frames();
+    // the frames are saved in the background
+    if let Some(window) = app.window(window) {
+        if let Err(e) = window.await_capture_frame_jobs() {
+            log(&format!("cannot save all the frames: {:?}", e));
+        }
+    }
+    match recorder.stop() {
+        Ok(Some(animation)) => log(&format!(
+            "recorded {} frames, in {}",
+            frames,
+            animation.display()
+        )),
+        Ok(None) => log(&format!("recorded {} frames", frames)),
+        Err(e) => log(&format!("cannot assemble the recording: {}", e)),
+    }
+}
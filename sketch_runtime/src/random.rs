@@ -0,0 +1,145 @@
+use std::num::ParseIntError;
+
+use nannou::rand::distributions::uniform::SampleUniform;
+use nannou::rand::distributions::{Distribution, Standard};
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Error, Rng, RngCore, SeedableRng};
+
+/// Random numbers of a sketch, drawn from a seed so that the same seed draws
+/// the same piece again.
+///
+/// Replaces nannou's `random_range` and `random`, which use the thread RNG.
+#[derive(Debug, Clone)]
+pub struct Random {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number between `min` and `max`, in any order, like nannou's `random_range`.
+    pub fn range<T: SampleUniform + PartialOrd>(&mut self, min: T, max: T) -> T {
+        if min == max {
+            min
+        } else if min < max {
+            self.rng.gen_range(min..max)
+        } else {
+            self.rng.gen_range(max..min)
+        }
+    }
+
+    /// Random value of any type, like nannou's `random`.
+    pub fn random<T>(&mut self) -> T
+    where
+        Standard: Distribution<T>,
+    {
+        self.rng.gen()
+    }
+
+    /// Seed for a noise function, e.g. `Perlin::new().set_seed(random.noise_seed())`.
+    pub fn noise_seed(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+}
+
+/// Lets [Random] be used with the `rand` traits, e.g. to shuffle or choose from slices.
+impl RngCore for Random {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Seed given on the command line, as `--seed <seed>` or `--seed=<seed>`.
+pub fn seed_from_args(
+    args: impl IntoIterator<Item = String>,
+) -> Result<Option<u64>, ParseIntError> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args.next().unwrap_or_default().parse().map(Some);
+        }
+        if let Some(seed) = arg.strip_prefix("--seed=") {
+            return seed.parse().map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// Seed given in the query of a page URL, as `?seed=<seed>`.
+pub fn seed_from_query(query: &str) -> Result<Option<u64>, ParseIntError> {
+    query
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|param| param.strip_prefix("seed="))
+        .map(str::parse)
+        .transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::random::{seed_from_args, seed_from_query, Random};
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let draw = |seed| {
+            let mut random = Random::new(seed);
+            (0..10)
+                .map(|_| random.range(0.0, 1.0))
+                .collect::<Vec<f32>>()
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+
+    #[test]
+    fn test_range() {
+        let mut random = Random::new(0);
+        for _ in 0..100 {
+            let x = random.range(3.0, -2.0);
+            assert!((-2.0..3.0).contains(&x));
+            let n = random.range(3, 10);
+            assert!((3..10).contains(&n));
+        }
+        assert_eq!(random.range(1.5, 1.5), 1.5);
+    }
+
+    #[test]
+    fn test_seed_from_args() {
+        let args = |args: &[&str]| seed_from_args(args.iter().map(|a| a.to_string()));
+        assert_eq!(args(&["art2"]), Ok(None));
+        assert_eq!(args(&["run", "art2", "--seed", "42"]), Ok(Some(42)));
+        assert_eq!(args(&["art2", "--seed=7"]), Ok(Some(7)));
+        assert!(args(&["art2", "--seed"]).is_err());
+        assert!(args(&["art2", "--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn test_seed_from_query() {
+        assert_eq!(seed_from_query(""), Ok(None));
+        assert_eq!(seed_from_query("?seed=42"), Ok(Some(42)));
+        assert_eq!(seed_from_query("?size=2&seed=7"), Ok(Some(7)));
+        assert!(seed_from_query("?seed=abc").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use nannou::event::Update;
+use nannou::image::ImageResult;
+
+use crate::animation::Animation;
+use crate::headless::frame_file;
+
+/// Directory of the recordings, next to where the sketch runs.
+pub const RECORDINGS: &str = "recordings";
+
+/// How a sketch is recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordOptions {
+    /// Records from the first frame, instead of waiting for the record key.
+    pub start: bool,
+    /// Animation assembled from the frames of each recording.
+    pub animation: Option<Animation>,
+    /// Frames per second of the recording, whatever the sketch runs at live.
+    pub fps: u32,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        RecordOptions {
+            start: false,
+            animation: None,
+            fps: 30,
+        }
+    }
+}
+
+/// Recording asked for on the command line: `--record` to record from the
+/// first frame, `--record=gif` or `--record=apng` to also assemble an
+/// animation, and `--fps <fps>` for its frame rate.
+pub fn record_options_from_args(
+    args: impl IntoIterator<Item = String>,
+) -> Result<RecordOptions, String> {
+    let mut options = RecordOptions::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--record" {
+            options.start = true;
+        } else if let Some(animation) = arg.strip_prefix("--record=") {
+            options.start = true;
+            options.animation = Some(animation.parse()?);
+        } else if arg == "--fps" || arg.starts_with("--fps=") {
+            let fps = match arg.strip_prefix("--fps=") {
+                Some(fps) => fps.to_string(),
+                None => args.next().unwrap_or_default(),
+            };
+            options.fps = fps
+                .parse()
+                .ok()
+                .filter(|&fps| fps > 0)
+                .ok_or_else(|| format!("invalid frame rate `{}`", fps))?;
+        }
+    }
+    Ok(options)
+}
+
+/// Records the frames of a sketch to numbered PNGs, one directory per
+/// recording, `recordings/<name>-<take>/` unless recorded elsewhere with
+/// [Recorder::with_dir].
+///
+/// Each recorded frame advances the sketch by the same timestep, so that the
+/// recording plays at its frame rate however slow drawing and saving are.
+#[derive(Debug)]
+pub struct Recorder {
+    name: String,
+    /// Directory the directories of the recordings go to.
+    dir: PathBuf,
+    options: RecordOptions,
+    takes: u32,
+    take: Option<Take>,
+    /// Time since the start on the clock of the sketch, which only runs at
+    /// the real pace when not recording.
+    clock: Duration,
+}
+
+#[derive(Debug)]
+struct Take {
+    dir: PathBuf,
+    frames: Vec<PathBuf>,
+}
+
+impl Recorder {
+    pub fn new(name: impl Into<String>, options: RecordOptions) -> Self {
+        Recorder {
+            name: name.into(),
+            dir: PathBuf::from(RECORDINGS),
+            options,
+            takes: 0,
+            take: None,
+            clock: Duration::ZERO,
+        }
+    }
+
+    pub fn with_dir(self, dir: impl Into<PathBuf>) -> Self {
+        Recorder {
+            dir: dir.into(),
+            ..self
+        }
+    }
+
+    pub fn options(&self) -> &RecordOptions {
+        &self.options
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.take.is_some()
+    }
+
+    /// Time between two recorded frames.
+    pub fn timestep(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.options.fps as f64)
+    }
+
+    /// Starts a new recording, in a new directory.
+    pub fn start(&mut self) -> std::io::Result<&Path> {
+        self.takes += 1;
+        let dir = self.dir.join(format!("{}-{}", self.name, self.takes));
+        std::fs::create_dir_all(&dir)?;
+        let take = self.take.insert(Take {
+            dir,
+            frames: Vec::new(),
+        });
+        Ok(&take.dir)
+    }
+
+    /// Moves the clock of the sketch to the next frame, `update` being the real
+    /// one, and gives the file to save that frame to when recording.
+    ///
+    /// The frame `n` of a take is at the time the take started plus `n`
+    /// timesteps. The clock goes on from there after the take, the time spent
+    /// recording is never caught up.
+    pub fn next_frame(&mut self, update: Update) -> (Update, Option<PathBuf>) {
+        let timestep = self.timestep();
+        let (since_last, file) = match self.take.as_mut() {
+            Some(take) => {
+                let frame = take.frames.len();
+                let file = frame_file(&take.dir, frame as u64);
+                take.frames.push(file.clone());
+                // the take starts whenever the previous frame is over
+                let since_last = if frame == 0 {
+                    update.since_last
+                } else {
+                    timestep
+                };
+                (since_last, Some(file))
+            }
+            None => (update.since_last, None),
+        };
+        self.clock += since_last;
+        let update = Update {
+            since_last,
+            since_start: self.clock,
+        };
+        (update, file)
+    }
+
+    /// Number of frames of the current recording.
+    pub fn frames(&self) -> usize {
+        self.take.as_ref().map_or(0, |take| take.frames.len())
+    }
+
+    /// Stops recording, and assembles the frames into the animation if one
+    /// was asked for. The frames must all be saved by then.
+    ///
+    /// Returns the animation file.
+    pub fn stop(&mut self) -> ImageResult<Option<PathBuf>> {
+        let take = match self.take.take() {
+            Some(take) if !take.frames.is_empty() => take,
+            _ => return Ok(None),
+        };
+        self.options
+            .animation
+            .map(|animation| {
+                let file = take.dir.with_extension(animation.extension());
+                animation.assemble(&take.frames, &file, self.options.fps)?;
+                Ok(file)
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use nannou::event::Update;
+
+    use crate::animation::Animation;
+    use crate::record::{record_options_from_args, RecordOptions, Recorder};
+
+    #[test]
+    fn test_record_options_from_args() {
+        let args = |args: &[&str]| record_options_from_args(args.iter().map(|a| a.to_string()));
+        assert_eq!(
+            args(&["art2", "--seed", "42"]),
+            Ok(RecordOptions::default())
+        );
+        assert_eq!(
+            args(&["art2", "--record"]),
+            Ok(RecordOptions {
+                start: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            args(&["art2", "--record=gif", "--fps", "60"]),
+            Ok(RecordOptions {
+                start: true,
+                animation: Some(Animation::Gif),
+                fps: 60,
+            })
+        );
+        assert_eq!(args(&["--fps=24"]).map(|o| o.fps), Ok(24));
+        assert!(args(&["--record=mp4"]).is_err());
+        assert!(args(&["--fps", "0"]).is_err());
+        assert!(args(&["--fps"]).is_err());
+    }
+
+    /// Since last and since start in milliseconds, and the file, of a frame
+    /// which took a second.
+    fn next_frame(recorder: &mut Recorder) -> (u128, u128, Option<PathBuf>) {
+        let (update, file) = recorder.next_frame(Update {
+            since_last: Duration::from_secs(1),
+            since_start: Duration::ZERO,
+        });
+        (
+            update.since_last.as_millis(),
+            update.since_start.as_millis(),
+            file,
+        )
+    }
+
+    #[test]
+    fn test_recorder() {
+        let recordings = std::env::temp_dir().join("sketch_runtime_test_recorder");
+        let mut recorder = Recorder::new(
+            "sketch",
+            RecordOptions {
+                fps: 4,
+                ..Default::default()
+            },
+        )
+        .with_dir(&recordings);
+        assert_eq!(next_frame(&mut recorder), (1000, 1000, None));
+
+        let dir = recorder.start().unwrap().to_path_buf();
+        assert_eq!(dir, recordings.join("sketch-1"));
+        assert!(dir.is_dir());
+        assert_eq!(
+            next_frame(&mut recorder),
+            (1000, 2000, Some(dir.join("0000.png")))
+        );
+        assert_eq!(
+            next_frame(&mut recorder),
+            (250, 2250, Some(dir.join("0001.png")))
+        );
+        assert_eq!(recorder.frames(), 2);
+
+        // no animation asked for
+        assert_eq!(recorder.stop().ok(), Some(None));
+        assert!(!recorder.is_recording());
+        // the clock goes on from the recorded time, in the next take too
+        assert_eq!(next_frame(&mut recorder), (1000, 3250, None));
+        let next = recorder.start().unwrap().to_path_buf();
+        assert_eq!(next, recordings.join("sketch-2"));
+        assert_eq!(
+            next_frame(&mut recorder),
+            (1000, 4250, Some(next.join("0000.png")))
+        );
+        assert_eq!(
+            next_frame(&mut recorder),
+            (250, 4500, Some(next.join("0001.png")))
+        );
+        recorder.stop().unwrap();
+        let _ = std::fs::remove_dir_all(recordings);
+    }
+}
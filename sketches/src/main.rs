@@ -0,0 +1,328 @@
+//! Launches any of the sketches natively by name, `sketches run art2`, or
+//! `sketches run art2 --seed 42` to draw the same piece again, and
+//! `sketches run art2 --record=gif` to record it.
+//!
+//! Sketches which can run headless are also rendered to PNG frames without a
+//! window, `sketches render art5 --frames 10 --out thumbnails/art5`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use nannou::image::ImageResult;
+use sketch_runtime::{Animation, RecordOptions};
+
+type Sketch = (&'static str, fn(u64, RecordOptions));
+type Render = (
+    &'static str,
+    fn(&Path, u32, u32, u64, u64) -> ImageResult<Vec<PathBuf>>,
+);
+
+/// All the sketches, each runs until its window is closed.
+const SKETCHES: &[Sketch] = &[
+    ("art1", sketch_runtime::run_seeded::<art1::Model>),
+    ("art2", sketch_runtime::run_seeded::<art2::Model>),
+    ("art3-tree", sketch_runtime::run_seeded::<art3_tree::Model>),
+    (
+        "art4-trees",
+        sketch_runtime::run_seeded::<art4_trees::Model>,
+    ),
+    (
+        "art5-flow-fields",
+        sketch_runtime::run_seeded::<art5_flow_fields_web::Model>,
+    ),
+    (
+        "art6-bezier-curves",
+        sketch_runtime::run_seeded::<art6_bezier_curves_web::Model>,
+    ),
+    (
+        "art6-flowers",
+        sketch_runtime::run_seeded::<art6_flowers_web::Model>,
+    ),
+];
+
+/// The sketches implementing [sketch_runtime::Headless].
+const HEADLESS: &[Render] = &[(
+    "art5-flow-fields",
+    sketch_runtime::render::<art5_flow_fields_web::Model>,
+)];
+
+const USAGE: &str = "usage: sketches run <sketch> [--seed <seed>] [--record[=gif|apng]] [--fps <fps>]
+       sketches render <sketch> [--frames <frames>] [--size <width>x<height>] [--seed <seed>] [--out <dir>] [--animation <gif|apng>] [--fps <fps>]
+       sketches list";
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["run", name, ref options @ ..] => match run(name, options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(message) => {
+                eprintln!("{}", message);
+                ExitCode::FAILURE
+            }
+        },
+        ["render", name, ref options @ ..] => match render(name, options) {
+            Ok(message) => {
+                println!("{}", message);
+                ExitCode::SUCCESS
+            }
+            Err(message) => {
+                eprintln!("{}", message);
+                ExitCode::FAILURE
+            }
+        },
+        ["list"] => {
+            for (name, _) in SKETCHES {
+                if HEADLESS.iter().any(|(n, _)| n == name) {
+                    println!("{} (render)", name);
+                } else {
+                    println!("{}", name);
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Finds a sketch by its name, or by the start of the name if only one sketch
+/// has it, so `art5` runs `art5-flow-fields`.
+fn find<T>(
+    sketches: &'static [(&'static str, T)],
+    name: &str,
+) -> Result<&'static (&'static str, T), String> {
+    if let Some(sketch) = sketches.iter().find(|(n, _)| *n == name) {
+        return Ok(sketch);
+    }
+    let candidates = sketches
+        .iter()
+        .filter(|(n, _)| n.starts_with(name))
+        .collect::<Vec<_>>();
+    match candidates[..] {
+        [sketch] => Ok(sketch),
+        [] => Err(format!("unknown sketch `{}`, see `sketches list`", name)),
+        _ => Err(format!(
+            "`{}` could be any of {}",
+            name,
+            candidates
+                .iter()
+                .map(|(n, _)| *n)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Runs a sketch in its window, with the options of the command line.
+fn run(name: &str, args: &[&str]) -> Result<(), String> {
+    let (_, run) = find(SKETCHES, name)?;
+    let options = RunOptions::parse(args)?;
+    run(
+        options.seed.unwrap_or_else(nannou::rand::random),
+        options.record,
+    );
+    Ok(())
+}
+
+/// Renders the frames of a headless sketch, with the options of the command line.
+fn render(name: &str, args: &[&str]) -> Result<String, String> {
+    let (name, render) = find(HEADLESS, name)?;
+    let options = RenderOptions::parse(name, args)?;
+    let seed = options.seed.unwrap_or_else(nannou::rand::random);
+    let (width, height) = options.size;
+    let files = render(&options.out, width, height, options.frames, seed)
+        .map_err(|e| format!("cannot render {}: {}", name, e))?;
+    let mut message = format!(
+        "{} frames of seed {} in {}",
+        files.len(),
+        seed,
+        options.out.display()
+    );
+    if let Some(animation) = options.animation {
+        let file = options.out.with_extension(animation.extension());
+        animation
+            .assemble(&files, &file, options.fps)
+            .map_err(|e| format!("cannot assemble {}: {}", file.display(), e))?;
+        message += &format!(", assembled in {}", file.display());
+    }
+    Ok(message)
+}
+
+/// Options of `sketches run`.
+#[derive(Debug, PartialEq)]
+struct RunOptions {
+    /// Random seed when not given.
+    seed: Option<u64>,
+    record: RecordOptions,
+}
+
+impl RunOptions {
+    /// Parses the options after the sketch name.
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let args = || args.iter().map(|arg| arg.to_string());
+        let seed = sketch_runtime::seed_from_args(args())
+            .map_err(|e| format!("invalid seed, {}\n{}", e, USAGE))?;
+        let record = sketch_runtime::record_options_from_args(args())
+            .map_err(|message| format!("{}\n{}", message, USAGE))?;
+        Ok(RunOptions { seed, record })
+    }
+}
+
+/// Options of `sketches render`.
+#[derive(Debug, PartialEq)]
+struct RenderOptions {
+    frames: u64,
+    size: (u32, u32),
+    /// Random seed when not given.
+    seed: Option<u64>,
+    out: PathBuf,
+    animation: Option<Animation>,
+    fps: u32,
+}
+
+impl RenderOptions {
+    /// Parses the options after the sketch name, the frames go to
+    /// `renders/<name>` by default.
+    fn parse(name: &str, args: &[&str]) -> Result<Self, String> {
+        let mut options = RenderOptions {
+            frames: 60,
+            size: (640, 480),
+            seed: None,
+            out: Path::new("renders").join(name),
+            animation: None,
+            fps: 30,
+        };
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            let (option, value) = match arg.split_once('=') {
+                Some((option, value)) => (option, Some(value)),
+                None => (arg, args.next().copied()),
+            };
+            let value = value.ok_or_else(|| format!("missing value of `{}`", option))?;
+            let invalid = || format!("invalid value `{}` of `{}`", value, option);
+            match option {
+                "--frames" => options.frames = value.parse().map_err(|_| invalid())?,
+                "--seed" => options.seed = Some(value.parse().map_err(|_| invalid())?),
+                "--size" => {
+                    options.size = value
+                        .split_once('x')
+                        .and_then(|(width, height)| {
+                            Some((width.parse().ok()?, height.parse().ok()?))
+                        })
+                        .ok_or_else(invalid)?;
+                }
+                "--out" => options.out = PathBuf::from(value),
+                "--animation" => options.animation = Some(value.parse()?),
+                "--fps" => {
+                    options.fps = value
+                        .parse()
+                        .ok()
+                        .filter(|&fps| fps > 0)
+                        .ok_or_else(invalid)?
+                }
+                _ => return Err(format!("unknown option `{}`\n{}", option, USAGE)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use sketch_runtime::{Animation, RecordOptions};
+
+    use crate::{find, RenderOptions, RunOptions, HEADLESS, SKETCHES};
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(SKETCHES, "art2").map(|s| s.0), Ok("art2"));
+        assert_eq!(find(SKETCHES, "art5").map(|s| s.0), Ok("art5-flow-fields"));
+        assert_eq!(
+            find(SKETCHES, "art6").map(|s| s.0),
+            Err("`art6` could be any of art6-bezier-curves, art6-flowers".to_string())
+        );
+        assert!(find(SKETCHES, "art7").is_err());
+        assert_eq!(find(HEADLESS, "art5").map(|s| s.0), Ok("art5-flow-fields"));
+        assert!(find(HEADLESS, "art2").is_err());
+    }
+
+    #[test]
+    fn test_run_options() {
+        let parse = RunOptions::parse;
+        assert_eq!(
+            parse(&[]),
+            Ok(RunOptions {
+                seed: None,
+                record: RecordOptions::default(),
+            })
+        );
+        assert_eq!(
+            parse(&["--seed", "42", "--record=gif", "--fps", "12"]),
+            Ok(RunOptions {
+                seed: Some(42),
+                record: RecordOptions {
+                    start: true,
+                    animation: Some(Animation::Gif),
+                    fps: 12,
+                },
+            })
+        );
+        assert!(parse(&["--seed", "abc"]).is_err());
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--record=mp4"]).is_err());
+        assert!(parse(&["--fps", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_render_options() {
+        let parse = RenderOptions::parse;
+        assert_eq!(
+            parse("art5", &[]),
+            Ok(RenderOptions {
+                frames: 60,
+                size: (640, 480),
+                seed: None,
+                out: PathBuf::from("renders/art5"),
+                animation: None,
+                fps: 30,
+            })
+        );
+        assert_eq!(
+            parse(
+                "art5",
+                &[
+                    "--frames",
+                    "10",
+                    "--size=320x240",
+                    "--seed",
+                    "42",
+                    "--out",
+                    "thumbs"
+                ]
+            ),
+            Ok(RenderOptions {
+                frames: 10,
+                size: (320, 240),
+                seed: Some(42),
+                out: PathBuf::from("thumbs"),
+                animation: None,
+                fps: 30,
+            })
+        );
+        assert_eq!(
+            parse("art5", &["--animation", "apng", "--fps=12"]).map(|o| (o.animation, o.fps)),
+            Ok((Some(Animation::Apng), 12))
+        );
+        assert!(parse("art5", &["--frames"]).is_err());
+        assert!(parse("art5", &["--size", "320"]).is_err());
+        assert!(parse("art5", &["--seed", "-1"]).is_err());
+        assert!(parse("art5", &["--animation", "mp4"]).is_err());
+        assert!(parse("art5", &["--fps", "0"]).is_err());
+        assert!(parse("art5", &["--speed", "2"]).is_err());
+    }
+}